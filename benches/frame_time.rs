@@ -0,0 +1,142 @@
+//! Measures the average frame time of a window showing cubes of growing dimensions, while the
+//! right layer keeps turning.
+//!
+//! Run with `cargo bench --bench frame_time`, or with `cargo bench --bench frame_time -- --owned`
+//! to give every piece a mesh of its own like it used to be. Cubes drawn in chunks have no pieces,
+//! so they aren't affected by it.
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use bevy::window::PresentMode;
+use rubiks_cube_rs::camera::{CameraPlugin, CameraSettings};
+use rubiks_cube_rs::history::History;
+use rubiks_cube_rs::puzzle::{PuzzleKind, PuzzleTurn};
+use rubiks_cube_rs::selection::{ActivePuzzle, SelectionPlugin};
+use rubiks_cube_rs::turn::{Piece, TurnPlugin, TurnQueue};
+use rubiks_cube_rs::view::ViewPlugin;
+use std::time::Duration;
+
+const DIMENSIONS: [u32; 4] = [3, 10, 25, 100];
+
+/// Frames after spawning a cube which aren't measured, e.g. because the meshes get uploaded.
+const WARM_UP_FRAMES: u32 = 60;
+const MEASURED_FRAMES: u32 = 600;
+
+fn main() {
+    let owned = std::env::args().any(|arg| arg == "--owned");
+
+    App::new()
+        .add_plugins(DefaultPlugins.set(WindowPlugin {
+            window: WindowDescriptor {
+                title: "frame time".to_string(),
+                // the frame time would be the refresh rate of the monitor otherwise
+                present_mode: PresentMode::AutoNoVsync,
+                ..default()
+            },
+            ..default()
+        }))
+        .add_plugin(CameraPlugin::new(CameraSettings::default()))
+        .add_plugin(ViewPlugin)
+        .add_plugin(TurnPlugin)
+        .init_resource::<History>()
+        .add_plugin(SelectionPlugin::new(ActivePuzzle {
+            kind: PuzzleKind::Rubik,
+            dimension: DIMENSIONS[0],
+        }))
+        .insert_resource(Benchmark {
+            owned,
+            dimension: 0,
+            frames: 0,
+            elapsed: Duration::ZERO,
+        })
+        .add_startup_system(setup)
+        .add_system(own_meshes.after("spawn_puzzle"))
+        .add_system(keep_turning)
+        .add_system(measure)
+        .run();
+}
+
+#[derive(Debug, Resource)]
+struct Benchmark {
+    owned: bool,
+    /// Index into [`DIMENSIONS`] of the cube which is shown.
+    dimension: usize,
+    frames: u32,
+    elapsed: Duration,
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn(DirectionalLightBundle {
+        directional_light: DirectionalLight {
+            illuminance: 50_000.0,
+            shadows_enabled: true,
+            ..default()
+        },
+        ..default()
+    });
+}
+
+/// Replaces the shared meshes of newly spawned pieces with copies of their own.
+fn own_meshes(
+    benchmark: Res<Benchmark>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut piece_query: Query<&mut Handle<Mesh>, Added<Piece>>,
+) {
+    if !benchmark.owned {
+        return;
+    }
+
+    for mut handle in piece_query.iter_mut() {
+        if let Some(mesh) = meshes.get(&handle).cloned() {
+            *handle = meshes.add(mesh);
+        }
+    }
+}
+
+/// Turns the right layer again as soon as the last turn is finished.
+fn keep_turning(mut turn_queue: ResMut<TurnQueue>) {
+    if turn_queue.is_idle() {
+        turn_queue.push(PuzzleTurn {
+            axis: 0,
+            layers: 0..=0,
+            amount: 1,
+        });
+    }
+}
+
+/// Switches to the next dimension once enough frames are measured and exits after the last.
+fn measure(
+    mut benchmark: ResMut<Benchmark>,
+    mut active_puzzle: ResMut<ActivePuzzle>,
+    mut exit_events: EventWriter<AppExit>,
+    time: Res<Time>,
+) {
+    benchmark.frames += 1;
+
+    if benchmark.frames <= WARM_UP_FRAMES {
+        return;
+    }
+
+    benchmark.elapsed += time.raw_delta();
+
+    if benchmark.frames < WARM_UP_FRAMES + MEASURED_FRAMES {
+        return;
+    }
+
+    let average = benchmark.elapsed / MEASURED_FRAMES;
+    println!(
+        "dimension {:>3}: {:>10.3?} per frame, {:>6.1} frames per second",
+        active_puzzle.dimension,
+        average,
+        1.0 / average.as_secs_f64()
+    );
+
+    benchmark.dimension += 1;
+    benchmark.frames = 0;
+    benchmark.elapsed = Duration::ZERO;
+
+    match DIMENSIONS.get(benchmark.dimension) {
+        Some(&dimension) => active_puzzle.dimension = dimension,
+        None => exit_events.send(AppExit),
+    }
+}
@@ -0,0 +1,82 @@
+//! Compares the time it takes to build the meshes of a cube when spawning it, once with the
+//! meshes shared between the pieces and once with a mesh for every piece like it used to be.
+//!
+//! Run with `cargo bench --bench meshes`.
+
+use bevy::prelude::Mesh;
+use rubiks_cube_rs::puzzle::rubiks::Rubik;
+use rubiks_cube_rs::puzzle::{Paint, Puzzle, PuzzleRender};
+use rubiks_cube_rs::scramble::Scramble;
+use std::time::{Duration, Instant};
+
+const DIMENSIONS: [u32; 3] = [3, 10, 25];
+const ROUNDS: u32 = 10;
+
+fn main() {
+    println!(
+        "{:>9} {:>7} | {:>7} {:>9} {:>10} | {:>7} {:>9} {:>10}",
+        "dimension", "pieces", "shared", "vertices", "time", "owned", "vertices", "time"
+    );
+
+    for dimension in DIMENSIONS {
+        let mut rubik = Rubik::new(dimension);
+
+        // a scrambled cube, so that the pieces aren't in the order they were created in
+        let scramble = Scramble::rubik(dimension, 0).to_string();
+        let turns = rubik
+            .parse_turns(&scramble)
+            .expect("scrambles can be parsed");
+
+        for turn in &turns {
+            rubik.turn(turn);
+        }
+
+        let (shared, shared_time) = measure(|| {
+            let meshes = rubik.create_meshes();
+            let transforms = (0..rubik.piece_meshes().len())
+                .map(|piece| rubik.get_piece_transform(piece))
+                .collect::<Vec<_>>();
+
+            (meshes, transforms)
+        });
+
+        // what every piece got before, which is the same as painting all of them
+        let stickers = rubik.stickers();
+        let (owned, owned_time) = measure(|| {
+            (0..rubik.piece_meshes().len())
+                .map(|piece| {
+                    let mesh = rubik.create_painted_mesh(piece, &stickers);
+                    (mesh, rubik.get_piece_transform(piece))
+                })
+                .collect::<Vec<_>>()
+        });
+
+        println!(
+            "{:>9} {:>7} | {:>7} {:>9} {:>10.3?} | {:>7} {:>9} {:>10.3?}",
+            dimension,
+            shared.1.len(),
+            shared.0.len(),
+            count_vertices(&shared.0),
+            shared_time,
+            owned.len(),
+            count_vertices(owned.iter().map(|(mesh, _)| mesh)),
+            owned_time,
+        );
+    }
+}
+
+/// Result of the last round and the average time of all rounds.
+fn measure<T>(mut f: impl FnMut() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let mut result = f();
+
+    for _ in 1..ROUNDS {
+        result = std::hint::black_box(f());
+    }
+
+    (result, start.elapsed() / ROUNDS)
+}
+
+fn count_vertices<'a>(meshes: impl IntoIterator<Item = &'a Mesh>) -> usize {
+    meshes.into_iter().map(Mesh::count_vertices).sum()
+}
@@ -0,0 +1,391 @@
+//! Commands to scramble or check solutions from scripts, which only need the puzzle models and
+//! solvers and build without the `render` feature.
+//!
+//! Every command prints a single JSON object to stdout, which has an `error` field if the
+//! command couldn't be run.
+
+use rubiks_cube_rs::notation::NotationError;
+use rubiks_cube_rs::puzzle::rubiks::state::{FaceletError, RubikState};
+use rubiks_cube_rs::puzzle::{Puzzle, PuzzleKind};
+use rubiks_cube_rs::scramble::random_seed;
+use rubiks_cube_rs::solver::kociemba::{Solver, DEFAULT_MAX_LENGTH};
+use rubiks_cube_rs::solver::SolveError;
+use serde_json::{json, Value};
+use std::fmt::{Display, Formatter};
+
+/// The command ran and its answer is yes, e.g. the solution solves the scramble.
+const EXIT_SUCCESS: i32 = 0;
+/// The command ran but its answer is no, e.g. the solution doesn't solve the scramble or the
+/// solver found no solution within the maximum length.
+const EXIT_FAILURE: i32 = 1;
+/// The arguments or the states and algorithms passed with them are invalid, which includes
+/// cubes that can't be solved at all.
+const EXIT_INVALID: i32 = 2;
+
+const USAGE: &str = "\
+usage: rubiks-cube-cli <command>
+
+commands:
+    scramble [--puzzle <puzzle>] [--seed <seed>]
+    solve <facelets> [--max-length <length>]
+    apply <algorithm> [--from <facelets> | --puzzle <puzzle>]
+    verify <scramble> <solution> [--puzzle <puzzle>]
+
+<puzzle> is NxN or NxNxN for a Rubik's Cube, pyraminx or mirror and defaults to 3x3.
+<facelets> are the stickers of a cube in URFDLB order.";
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    std::process::exit(run(&args));
+}
+
+/// Runs the command given by the arguments, which don't include the program name, and returns
+/// the exit code.
+fn run(args: &[String]) -> i32 {
+    let (output, code) = match execute(args) {
+        Ok(output) => output,
+        Err(error) => {
+            if let CliError::Usage(_) = error {
+                eprintln!("{}", USAGE);
+            }
+
+            (json!({ "error": error.to_string() }), EXIT_INVALID)
+        }
+    };
+
+    println!("{}", output);
+    code
+}
+
+fn execute(args: &[String]) -> Result<(Value, i32), CliError> {
+    let Some((command, args)) = args.split_first() else {
+        return Err(CliError::Usage("expected a command".to_string()));
+    };
+
+    match command.as_str() {
+        "scramble" => scramble(args),
+        "solve" => solve(args),
+        "apply" => apply(args),
+        "verify" => verify(args),
+        _ => Err(CliError::Usage(format!("unknown command {}", command))),
+    }
+}
+
+#[derive(Debug)]
+enum CliError {
+    Usage(String),
+    Puzzle(String),
+    Facelets(FaceletError),
+    Notation(NotationError),
+    Solve(SolveError),
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::Usage(message) => write!(f, "{}", message),
+            CliError::Puzzle(puzzle) => write!(f, "there is no puzzle called {:?}", puzzle),
+            CliError::Facelets(error) => write!(f, "invalid facelets: {}", error),
+            CliError::Notation(error) => write!(f, "invalid algorithm: {}", error),
+            CliError::Solve(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<FaceletError> for CliError {
+    fn from(error: FaceletError) -> Self {
+        CliError::Facelets(error)
+    }
+}
+
+impl From<NotationError> for CliError {
+    fn from(error: NotationError) -> Self {
+        CliError::Notation(error)
+    }
+}
+
+/// Positional arguments and the values of the options which were allowed.
+struct Arguments<'a> {
+    positional: Vec<&'a str>,
+    options: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Arguments<'a> {
+    fn parse(
+        args: &'a [String],
+        positional: usize,
+        allowed_options: &[&str],
+    ) -> Result<Self, CliError> {
+        let mut parsed = Arguments {
+            positional: Vec::new(),
+            options: Vec::new(),
+        };
+
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            if !arg.starts_with("--") {
+                parsed.positional.push(arg);
+                continue;
+            }
+
+            if !allowed_options.contains(&arg.as_str()) {
+                return Err(CliError::Usage(format!("unknown option {}", arg)));
+            }
+
+            let Some(value) = args.next() else {
+                return Err(CliError::Usage(format!("{} needs a value", arg)));
+            };
+
+            parsed.options.push((arg, value));
+        }
+
+        if parsed.positional.len() != positional {
+            return Err(CliError::Usage(format!(
+                "expected {} arguments but got {}",
+                positional,
+                parsed.positional.len()
+            )));
+        }
+
+        Ok(parsed)
+    }
+
+    fn option(&self, name: &str) -> Option<&'a str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(option, _)| *option == name)
+            .map(|(_, value)| *value)
+    }
+
+    fn puzzle(&self) -> Result<(PuzzleKind, u32), CliError> {
+        self.option("--puzzle")
+            .map_or(Ok((PuzzleKind::Rubik, 3)), parse_puzzle)
+    }
+}
+
+/// `NxN` and `NxNxN` are Rubik's Cubes, the other puzzles are given by name.
+fn parse_puzzle(puzzle: &str) -> Result<(PuzzleKind, u32), CliError> {
+    let (kind, dimension) = match puzzle.to_ascii_lowercase().as_str() {
+        "pyraminx" => (
+            PuzzleKind::Pyraminx,
+            PuzzleKind::Pyraminx.entry().default_dimension,
+        ),
+        "mirror" => (
+            PuzzleKind::Mirror,
+            PuzzleKind::Mirror.entry().default_dimension,
+        ),
+        cube => {
+            let sides = cube
+                .split('x')
+                .map(str::parse::<u32>)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| CliError::Puzzle(puzzle.to_string()))?;
+
+            match sides.as_slice() {
+                [n, _] | [n, _, _] if sides.iter().all(|side| side == n) => (PuzzleKind::Rubik, *n),
+                _ => return Err(CliError::Puzzle(puzzle.to_string())),
+            }
+        }
+    };
+
+    if !kind.entry().dimensions.contains(&dimension) {
+        return Err(CliError::Puzzle(puzzle.to_string()));
+    }
+
+    Ok((kind, dimension))
+}
+
+/// How the puzzle is called in the output, which can be passed to `--puzzle` again.
+fn puzzle_name(kind: PuzzleKind, dimension: u32) -> String {
+    match kind {
+        PuzzleKind::Rubik => format!("{0}x{0}x{0}", dimension),
+        PuzzleKind::Pyraminx => "pyraminx".to_string(),
+        PuzzleKind::Mirror => "mirror".to_string(),
+    }
+}
+
+/// Applies the algorithm in the notation of the puzzle.
+fn apply_algorithm(puzzle: &mut dyn Puzzle, algorithm: &str) -> Result<(), CliError> {
+    for turn in &puzzle.parse_turns(algorithm)? {
+        puzzle.turn(turn);
+    }
+
+    Ok(())
+}
+
+fn scramble(args: &[String]) -> Result<(Value, i32), CliError> {
+    let args = Arguments::parse(args, 0, &["--puzzle", "--seed"])?;
+    let (kind, dimension) = args.puzzle()?;
+
+    let seed = match args.option("--seed") {
+        Some(seed) => seed
+            .parse::<u64>()
+            .map_err(|_| CliError::Usage(format!("the seed {:?} is no number", seed)))?,
+        None => random_seed(),
+    };
+
+    let puzzle = (kind.entry().create)(dimension);
+    let scramble = puzzle
+        .scramble(seed)
+        .moves
+        .iter()
+        .map(|turn| puzzle.format_turn(turn))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut output = json!({
+        "puzzle": puzzle_name(kind, dimension),
+        "seed": seed,
+        "scramble": scramble,
+    });
+
+    if kind == PuzzleKind::Rubik {
+        let mut state = RubikState::new(dimension);
+        apply_algorithm(&mut state, &scramble)?;
+        output["state"] = json!(state.to_facelet_string());
+    }
+
+    Ok((output, EXIT_SUCCESS))
+}
+
+fn solve(args: &[String]) -> Result<(Value, i32), CliError> {
+    let args = Arguments::parse(args, 1, &["--max-length"])?;
+    let state = RubikState::from_facelet_string(args.positional[0])?;
+
+    let max_length = match args.option("--max-length") {
+        Some(length) => length
+            .parse::<usize>()
+            .map_err(|_| CliError::Usage(format!("the length {:?} is no number", length)))?,
+        None => DEFAULT_MAX_LENGTH,
+    };
+
+    // checked before the solver gets its tables, which takes a while the first time
+    if state.dimension() != 3 {
+        return Err(CliError::Solve(SolveError::WrongDimension(
+            state.dimension(),
+        )));
+    }
+
+    match Solver::shared().solve_state(&state, max_length) {
+        Ok(solution) => {
+            let solution = solution.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+            Ok((
+                json!({ "solution": solution.join(" "), "length": solution.len() }),
+                EXIT_SUCCESS,
+            ))
+        }
+        Err(SolveError::NotFound) => Ok((
+            json!({ "error": SolveError::NotFound.to_string() }),
+            EXIT_FAILURE,
+        )),
+        Err(error) => Err(CliError::Solve(error)),
+    }
+}
+
+fn apply(args: &[String]) -> Result<(Value, i32), CliError> {
+    let args = Arguments::parse(args, 1, &["--from", "--puzzle"])?;
+
+    let mut state = match (args.option("--from"), args.option("--puzzle")) {
+        (Some(_), Some(_)) => {
+            return Err(CliError::Usage(
+                "--from and --puzzle can't be used together".to_string(),
+            ))
+        }
+        (Some(facelets), None) => RubikState::from_facelet_string(facelets)?,
+        (None, _) => match args.puzzle()? {
+            (PuzzleKind::Rubik, dimension) => RubikState::new(dimension),
+            _ => {
+                return Err(CliError::Usage(
+                    "only the states of Rubik's Cubes can be printed".to_string(),
+                ))
+            }
+        },
+    };
+
+    apply_algorithm(&mut state, args.positional[0])?;
+
+    Ok((
+        json!({
+            "state": state.to_facelet_string(),
+            "solved": state.is_solved(),
+        }),
+        EXIT_SUCCESS,
+    ))
+}
+
+fn verify(args: &[String]) -> Result<(Value, i32), CliError> {
+    let args = Arguments::parse(args, 2, &["--puzzle"])?;
+    let (kind, dimension) = args.puzzle()?;
+
+    let mut puzzle = (kind.entry().create)(dimension);
+    apply_algorithm(puzzle.as_mut(), args.positional[0])?;
+    apply_algorithm(puzzle.as_mut(), args.positional[1])?;
+
+    let solved = puzzle.is_solved();
+    let code = if solved { EXIT_SUCCESS } else { EXIT_FAILURE };
+
+    Ok((json!({ "solved": solved }), code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rubiks_cube_rs::solver::kociemba::CACHE_DIR_VARIABLE;
+    use std::sync::Once;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        // set before any test solves a cube, so that the tables aren't cached for the user
+        static CACHE_DIR: Once = Once::new();
+        CACHE_DIR.call_once(|| {
+            std::env::set_var(CACHE_DIR_VARIABLE, std::env::temp_dir());
+        });
+
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn scrambles_are_solved_by_their_inverse() {
+        for name in ["2x2", "4x4x4", "pyraminx", "mirror"] {
+            let (output, code) =
+                execute(&args(&["scramble", "--puzzle", name, "--seed", "5"])).unwrap();
+            assert_eq!(code, EXIT_SUCCESS);
+
+            let (kind, dimension) = parse_puzzle(name).unwrap();
+            let puzzle = (kind.entry().create)(dimension);
+            let scramble = output["scramble"].as_str().unwrap();
+
+            let inverse = puzzle
+                .parse_turns(scramble)
+                .unwrap()
+                .iter()
+                .rev()
+                .map(|turn| puzzle.format_turn(&turn.inverse()))
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            let solved = execute(&args(&["verify", scramble, &inverse, "--puzzle", name]));
+            assert_eq!(solved.unwrap(), (json!({ "solved": true }), EXIT_SUCCESS));
+
+            let unsolved = execute(&args(&["verify", scramble, "", "--puzzle", name]));
+            assert_eq!(
+                unsolved.unwrap(),
+                (json!({ "solved": false }), EXIT_FAILURE)
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_arguments_and_unsolvable_cubes_exit_with_invalid() {
+        // the corner between U, R and F is twisted in place
+        let twisted = "UUUUUUUURFRRRRRRRRFFUFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB";
+
+        assert_eq!(run(&args(&["solve", twisted])), EXIT_INVALID);
+        assert_eq!(run(&args(&["apply", "R", "--puzzle", "3x4"])), EXIT_INVALID);
+        assert_eq!(run(&args(&["apply", "R", "--from", twisted])), EXIT_INVALID);
+        assert_eq!(run(&args(&["unknown"])), EXIT_INVALID);
+        assert_eq!(run(&[]), EXIT_INVALID);
+    }
+}
@@ -32,6 +32,16 @@ impl Plugin for CameraPlugin {
     }
 }
 
+#[cfg(debug_assertions)]
+const MODE_SWITCH_KEY: KeyCode = KeyCode::C;
+
+#[cfg(debug_assertions)]
+const CURSOR_GRAB_KEY: KeyCode = KeyCode::Escape;
+
+/// Keys of the debug camera.
+#[cfg(debug_assertions)]
+pub const RESERVED_KEYS: [KeyCode; 2] = [MODE_SWITCH_KEY, CURSOR_GRAB_KEY];
+
 #[cfg(debug_assertions)]
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Mode {
@@ -114,7 +124,7 @@ fn mode_switch(
     mut fly_camera: ResMut<FlyCamera>,
     mut query: Query<&mut Transform, With<Camera>>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::C) {
+    if keyboard_input.just_pressed(MODE_SWITCH_KEY) {
         let window = windows.get_primary_mut().unwrap();
         let mut transform = query.single_mut();
 
@@ -151,7 +161,7 @@ fn is_flying(camera_settings: Res<CameraSettings>) -> ShouldRun {
 
 #[cfg(debug_assertions)]
 fn cursor_grab(keyboard_input: Res<Input<KeyCode>>, mut windows: ResMut<Windows>) {
-    if keyboard_input.just_pressed(KeyCode::Escape) {
+    if keyboard_input.just_pressed(CURSOR_GRAB_KEY) {
         let window = windows.get_primary_mut().unwrap();
         window.set_cursor_visibility(!window.cursor_visible());
 
@@ -14,7 +14,46 @@ pub const YELLOW: Color = Color::rgb(0.992, 0.8, 0.035);
 pub const WHITE: Color = Color::WHITE;
 /// <div style="background-color:rgb(22%, 22%, 22%); width: 10px; padding: 10px; border: 1px solid;"></div>
 pub const GRAY: Color = Color::rgb(0.22, 0.22, 0.22);
+/// <div style="background-color:rgb(6%, 6%, 6%); width: 10px; padding: 10px; border: 1px solid;"></div>
+pub const BLACK: Color = Color::rgb(0.06, 0.06, 0.06);
 /// <div style="background-color:rgb(100%, 86.27%, 61.57%); width: 10px; padding: 10px; border: 1px solid;"></div>
 pub const GOLD: Color = Color::rgb(1.0, 0.8627, 0.6157);
 /// <div style="background-color:rgb(100%, 97.65%, 96.01%); width: 10px; padding: 10px; border: 1px solid;"></div>
 pub const SILVER: Color = Color::rgb(1.0, 0.9765, 0.9601);
+
+/// Colors of the palette by Okabe and Ito, which stay distinguishable with the common kinds of
+/// color blindness.
+pub mod okabe_ito {
+    use bevy::prelude::Color;
+
+    /// <div style="background-color:rgb(90.2%, 62.4%, 0%); width: 10px; padding: 10px; border: 1px solid;"></div>
+    pub const ORANGE: Color = Color::rgb(0.902, 0.624, 0.0);
+    /// <div style="background-color:rgb(33.7%, 70.6%, 91.4%); width: 10px; padding: 10px; border: 1px solid;"></div>
+    pub const SKY_BLUE: Color = Color::rgb(0.337, 0.706, 0.914);
+    /// <div style="background-color:rgb(0%, 62%, 45.1%); width: 10px; padding: 10px; border: 1px solid;"></div>
+    pub const BLUISH_GREEN: Color = Color::rgb(0.0, 0.62, 0.451);
+    /// <div style="background-color:rgb(94.1%, 89.4%, 25.9%); width: 10px; padding: 10px; border: 1px solid;"></div>
+    pub const YELLOW: Color = Color::rgb(0.941, 0.894, 0.259);
+    /// <div style="background-color:rgb(0%, 44.7%, 69.8%); width: 10px; padding: 10px; border: 1px solid;"></div>
+    pub const BLUE: Color = Color::rgb(0.0, 0.447, 0.698);
+    /// <div style="background-color:rgb(83.5%, 36.9%, 0%); width: 10px; padding: 10px; border: 1px solid;"></div>
+    pub const VERMILLION: Color = Color::rgb(0.835, 0.369, 0.0);
+    /// <div style="background-color:rgb(80%, 47.5%, 65.5%); width: 10px; padding: 10px; border: 1px solid;"></div>
+    pub const REDDISH_PURPLE: Color = Color::rgb(0.8, 0.475, 0.655);
+}
+
+/// Fully saturated colors, which are easier to tell apart on dim screens.
+pub mod high_contrast {
+    use bevy::prelude::Color;
+
+    /// <div style="background-color:rgb(100%, 0%, 0%); width: 10px; padding: 10px; border: 1px solid;"></div>
+    pub const RED: Color = Color::rgb(1.0, 0.0, 0.0);
+    /// <div style="background-color:rgb(100%, 55%, 0%); width: 10px; padding: 10px; border: 1px solid;"></div>
+    pub const ORANGE: Color = Color::rgb(1.0, 0.55, 0.0);
+    /// <div style="background-color:rgb(100%, 100%, 0%); width: 10px; padding: 10px; border: 1px solid;"></div>
+    pub const YELLOW: Color = Color::rgb(1.0, 1.0, 0.0);
+    /// <div style="background-color:rgb(0%, 20%, 100%); width: 10px; padding: 10px; border: 1px solid;"></div>
+    pub const BLUE: Color = Color::rgb(0.0, 0.2, 1.0);
+    /// <div style="background-color:rgb(0%, 80%, 0%); width: 10px; padding: 10px; border: 1px solid;"></div>
+    pub const GREEN: Color = Color::rgb(0.0, 0.8, 0.0);
+}
@@ -0,0 +1,238 @@
+use crate::history::History;
+use crate::player::Player;
+use crate::puzzle::{CurrentPuzzle, PuzzleRender, Styles};
+use crate::selection::{ActivePuzzle, SpawnedPuzzle};
+use crate::turn::{Piece, TurnQueue};
+use bevy::prelude::*;
+
+const EDIT_KEY: KeyCode = KeyCode::F5;
+const DISCARD_KEY: KeyCode = KeyCode::Delete;
+
+/// Keys starting and finishing painting.
+pub const RESERVED_KEYS: [KeyCode; 2] = [EDIT_KEY, DISCARD_KEY];
+
+/// Keys which pick the color to paint with while painting, in the order of the colors of the
+/// puzzle. Turning is off while painting, so they may be bound to moves as well.
+const COLOR_KEYS: [KeyCode; 9] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+/// Goes back to cycling through the colors with each click.
+const CYCLE_KEY: KeyCode = KeyCode::Key0;
+
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Editor>()
+            .add_event::<PaintEvent>()
+            .add_system(
+                edit_with_keyboard
+                    .label("edit_puzzle")
+                    .after("spawn_puzzle"),
+            )
+            .add_system(paint_stickers.after("edit_puzzle"));
+    }
+}
+
+/// Clicked sticker, given by the piece and the outward normal of its side in view space.
+#[derive(Debug, Copy, Clone)]
+pub struct PaintEvent {
+    pub piece: usize,
+    pub normal: Vec3,
+}
+
+/// Lets the stickers be painted one by one, e.g. to enter the scramble of a real puzzle. The
+/// painted colors are only applied once they describe a state which can be reached by turning.
+///
+/// Nothing turns the puzzle while painting.
+#[derive(Debug, Default, Resource)]
+pub struct Editor {
+    /// Colors of the stickers while painting, `None` otherwise.
+    stickers: Option<Vec<usize>>,
+    /// Color clicked stickers get, cycling through the colors with each click if there is none.
+    brush: Option<usize>,
+}
+
+impl Editor {
+    pub fn is_painting(&self) -> bool {
+        self.stickers.is_some()
+    }
+}
+
+fn edit_with_keyboard(
+    mut editor: ResMut<Editor>,
+    mut history: ResMut<History>,
+    mut player: ResMut<Player>,
+    puzzle: Option<ResMut<CurrentPuzzle>>,
+    spawned_puzzle: Res<SpawnedPuzzle>,
+    turn_queue: Res<TurnQueue>,
+    active_puzzle: Res<ActivePuzzle>,
+    styles: Res<Styles>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut piece_query: Query<(&Piece, &mut Handle<Mesh>, &mut Transform)>,
+) {
+    // a newly selected puzzle replaces the painted one, and a new style respawns the puzzle
+    // without the painted meshes
+    if active_puzzle.is_changed() || styles.is_changed() {
+        if editor.is_painting() {
+            editor.stickers = None;
+        }
+
+        return;
+    }
+
+    let Some(mut puzzle) = puzzle else {
+        return;
+    };
+
+    let Some(stickers) = &editor.stickers else {
+        if !keyboard_input.just_pressed(EDIT_KEY) {
+            return;
+        }
+
+        let Some(paint) = puzzle.0.paint() else {
+            warn!("the stickers of this puzzle can't be painted");
+            return;
+        };
+
+        // painted stickers are shown by giving their piece a mesh of its own
+        if puzzle.0.chunks().is_some() {
+            warn!("the stickers of puzzles drawn in chunks can't be painted");
+            return;
+        }
+
+        // the painted stickers would be off as soon as the turns get applied
+        if !turn_queue.is_idle() {
+            warn!("the stickers can only be painted once all turns are finished");
+            return;
+        }
+
+        editor.stickers = Some(paint.stickers());
+        info!(
+            "painting stickers, {:?} applies and {:?} discards them",
+            EDIT_KEY, DISCARD_KEY
+        );
+        return;
+    };
+
+    if keyboard_input.just_pressed(DISCARD_KEY) {
+        editor.stickers = None;
+        reset_pieces(puzzle.0.as_ref(), &spawned_puzzle, &mut piece_query);
+        info!("discarded the painted stickers");
+        return;
+    }
+
+    if keyboard_input.just_pressed(EDIT_KEY) {
+        let Some(paint) = puzzle.0.paint_mut() else {
+            return;
+        };
+
+        match paint.set_stickers(stickers) {
+            Ok(()) => {
+                // neither the turns so far nor the algorithm lead to the painted state
+                history.start_from(stickers.clone());
+                editor.stickers = None;
+                player.rewind();
+                reset_pieces(puzzle.0.as_ref(), &spawned_puzzle, &mut piece_query);
+                info!("applied the painted stickers");
+            }
+            Err(error) => warn!("can't apply the painted stickers: {}", error),
+        }
+
+        return;
+    }
+
+    let color_count = puzzle.0.paint().map_or(0, |paint| paint.color_count());
+
+    for (color, key) in COLOR_KEYS.into_iter().take(color_count).enumerate() {
+        if keyboard_input.just_pressed(key) {
+            editor.brush = Some(color);
+            info!("painting with color {}", color + 1);
+        }
+    }
+
+    if keyboard_input.just_pressed(CYCLE_KEY) {
+        editor.brush = None;
+        info!("cycling through the colors");
+    }
+}
+
+/// Paints the clicked stickers, which gives their piece a mesh of its own but doesn't change the
+/// state.
+fn paint_stickers(
+    mut editor: ResMut<Editor>,
+    mut paint_events: EventReader<PaintEvent>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    puzzle: Option<Res<CurrentPuzzle>>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut piece_query: Query<(&Piece, &mut Handle<Mesh>)>,
+) {
+    let Some(paint) = puzzle.as_ref().and_then(|puzzle| puzzle.0.paint()) else {
+        paint_events.clear();
+        return;
+    };
+
+    let brush = editor.brush;
+    let backwards = keyboard_input.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+    let color_count = paint.color_count();
+
+    let Some(stickers) = editor.stickers.as_mut() else {
+        paint_events.clear();
+        return;
+    };
+
+    for event in paint_events.iter() {
+        let Some(sticker) = paint.sticker_index(event.piece, event.normal) else {
+            continue;
+        };
+
+        // holding shift cycles backwards
+        stickers[sticker] = brush.unwrap_or(if backwards {
+            (stickers[sticker] + color_count - 1) % color_count
+        } else {
+            (stickers[sticker] + 1) % color_count
+        });
+
+        let mesh = piece_query
+            .iter_mut()
+            .find(|(piece, _)| piece.0 == event.piece)
+            .map(|(_, mesh)| mesh);
+
+        // the shared mesh stays as it is for the other pieces, the painted one gets freed as
+        // soon as no piece uses it anymore
+        if let Some(mut mesh) = mesh {
+            *mesh = meshes.add(paint.create_painted_mesh(event.piece, stickers));
+        }
+    }
+}
+
+/// Shows the pieces as they are in the state of the puzzle again, with their shared meshes
+/// instead of the painted ones.
+fn reset_pieces(
+    puzzle: &dyn PuzzleRender,
+    spawned_puzzle: &SpawnedPuzzle,
+    piece_query: &mut Query<(&Piece, &mut Handle<Mesh>, &mut Transform)>,
+) {
+    let piece_meshes = puzzle.piece_meshes();
+
+    for (piece, mut mesh, mut transform) in piece_query.iter_mut() {
+        let Some(shared) = piece_meshes
+            .get(piece.0)
+            .and_then(|&index| spawned_puzzle.meshes.get(index))
+        else {
+            continue;
+        };
+
+        *mesh = shared.clone();
+        *transform = puzzle.get_piece_transform(piece.0);
+    }
+}
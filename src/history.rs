@@ -0,0 +1,219 @@
+use crate::editor::Editor;
+use crate::puzzle::{merge_turns, CurrentPuzzle, Puzzle, PuzzleRender, PuzzleTurn};
+use crate::turn::{TurnEvent, TurnQueue};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub const UNDO_KEY: KeyCode = KeyCode::Z;
+pub const REDO_KEY: KeyCode = KeyCode::Y;
+
+pub struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<History>()
+            .add_system(record_turns.label("record_turns"))
+            .add_system(undo_with_keyboard.after("record_turns"));
+    }
+}
+
+/// Every turn requested for the current puzzle, no matter if it came from dragging, the keyboard
+/// or an algorithm, which together with the state they started from describes the state of the
+/// puzzle.
+#[derive(Debug, Clone, Default, PartialEq, Resource, Serialize, Deserialize)]
+pub struct History {
+    /// Painted stickers the turns started from, ordered like
+    /// [`Paint::stickers`](crate::puzzle::Paint::stickers), or `None` for the solved state.
+    start: Option<Vec<usize>>,
+    /// Applied turns followed by the undone ones, which are kept until a new turn gets recorded.
+    turns: Vec<PuzzleTurn>,
+    /// Number of turns which are applied.
+    applied: usize,
+}
+
+impl History {
+    /// Adds a turn and forgets the undone turns, as they can't be redone after it anymore.
+    pub fn record(&mut self, turn: PuzzleTurn) {
+        self.turns.truncate(self.applied);
+        self.turns.push(turn);
+        self.applied += 1;
+    }
+
+    /// Returns the turn which reverts the last applied turn.
+    pub fn undo(&mut self) -> Option<PuzzleTurn> {
+        self.applied = self.applied.checked_sub(1)?;
+        Some(self.turns[self.applied].inverse())
+    }
+
+    /// Returns the last undone turn so that it can be applied again.
+    pub fn redo(&mut self) -> Option<PuzzleTurn> {
+        let turn = self.turns.get(self.applied)?.clone();
+        self.applied += 1;
+        Some(turn)
+    }
+
+    pub fn clear(&mut self) {
+        self.start = None;
+        self.turns.clear();
+        self.applied = 0;
+    }
+
+    /// Forgets all turns, as the puzzle continues from the painted stickers.
+    pub fn start_from(&mut self, stickers: Vec<usize>) {
+        self.clear();
+        self.start = Some(stickers);
+    }
+
+    pub fn start(&self) -> Option<&[usize]> {
+        self.start.as_deref()
+    }
+
+    /// Whether every turn exists on the given puzzle, e.g. after the history got loaded from a
+    /// file. The start only gets checked once it is painted onto the puzzle.
+    pub fn fits(&self, puzzle: &dyn Puzzle) -> bool {
+        let turns_fit = self.turns.iter().all(|turn| {
+            turn.axis < puzzle.axis_count()
+                && turn.layers.start() <= turn.layers.end()
+                && *turn.layers.end() < puzzle.layer_count(turn.axis)
+        });
+
+        turns_fit && self.applied <= self.turns.len()
+    }
+
+    /// The applied turns exactly as they were turned, e.g. to reconstruct a solve.
+    pub fn raw(&self) -> &[PuzzleTurn] {
+        &self.turns[..self.applied]
+    }
+
+    /// The applied turns with consecutive turns of the same layers merged, so that `R R` shows
+    /// up as `R2` and `R R'` not at all.
+    pub fn merged(&self, steps_per_revolution: i32) -> Vec<PuzzleTurn> {
+        merge_turns(self.raw(), steps_per_revolution)
+    }
+}
+
+fn record_turns(
+    mut turn_events: EventReader<TurnEvent>,
+    mut history: ResMut<History>,
+    puzzle: Option<Res<CurrentPuzzle>>,
+) {
+    let mut recorded = false;
+
+    for event in turn_events.iter() {
+        history.record(event.0.clone());
+        recorded = true;
+    }
+
+    if let (true, Some(puzzle)) = (recorded, puzzle) {
+        log_history(&history, puzzle.0.as_ref());
+    }
+}
+
+/// Undone and redone turns go straight into the queue, so that they are not recorded again.
+fn undo_with_keyboard(
+    mut history: ResMut<History>,
+    mut turn_queue: ResMut<TurnQueue>,
+    puzzle: Option<Res<CurrentPuzzle>>,
+    editor: Res<Editor>,
+    keyboard_input: Res<Input<KeyCode>>,
+) {
+    let Some(puzzle) = puzzle else {
+        return;
+    };
+
+    if editor.is_painting() || !keyboard_input.any_pressed([KeyCode::LControl, KeyCode::RControl]) {
+        return;
+    }
+
+    let turn = if keyboard_input.just_pressed(UNDO_KEY) {
+        history.undo()
+    } else if keyboard_input.just_pressed(REDO_KEY) {
+        history.redo()
+    } else {
+        return;
+    };
+
+    if let Some(turn) = turn {
+        turn_queue.push(turn);
+        log_history(&history, puzzle.0.as_ref());
+    }
+}
+
+fn log_history(history: &History, puzzle: &dyn PuzzleRender) {
+    let moves = history
+        .merged(puzzle.steps_per_revolution())
+        .iter()
+        .map(|turn| puzzle.format_turn(turn))
+        .collect::<Vec<_>>();
+
+    info!("history: {}", moves.join(" "));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(axis: usize, amount: i32) -> PuzzleTurn {
+        PuzzleTurn {
+            axis,
+            layers: 0..=0,
+            amount,
+        }
+    }
+
+    fn history(turns: &[PuzzleTurn]) -> History {
+        let mut history = History::default();
+
+        for turn in turns {
+            history.record(turn.clone());
+        }
+
+        history
+    }
+
+    #[test]
+    fn merges_turns_of_the_same_layers() {
+        assert!(history(&[turn(0, 1), turn(0, -1)]).merged(4).is_empty());
+        assert_eq!(history(&[turn(0, 1), turn(0, 1)]).merged(4), [turn(0, 2)]);
+        assert_eq!(
+            history(&[turn(0, 1), turn(0, 1)]).raw(),
+            [turn(0, 1), turn(0, 1)]
+        );
+    }
+
+    #[test]
+    fn undo_after_a_merge_reverts_the_last_turn() {
+        let mut history = history(&[turn(1, 1), turn(0, 1), turn(0, 1)]);
+
+        assert_eq!(history.undo(), Some(turn(0, -1)));
+        assert_eq!(history.merged(4), [turn(1, 1), turn(0, 1)]);
+        assert_eq!(history.undo(), Some(turn(0, -1)));
+        assert_eq!(history.undo(), Some(turn(1, -1)));
+        assert_eq!(history.undo(), None);
+        assert!(history.merged(4).is_empty());
+    }
+
+    #[test]
+    fn redo_applies_the_undone_turns_again() {
+        let mut history = history(&[turn(0, 1), turn(1, 2)]);
+
+        history.undo();
+        history.undo();
+
+        assert_eq!(history.redo(), Some(turn(0, 1)));
+        assert_eq!(history.redo(), Some(turn(1, 2)));
+        assert_eq!(history.redo(), None);
+        assert_eq!(history.raw(), [turn(0, 1), turn(1, 2)]);
+    }
+
+    #[test]
+    fn recording_after_an_undo_drops_the_undone_turns() {
+        let mut history = history(&[turn(0, 1), turn(1, 1)]);
+
+        history.undo();
+        history.record(turn(2, 1));
+
+        assert_eq!(history.redo(), None);
+        assert_eq!(history.raw(), [turn(0, 1), turn(2, 1)]);
+    }
+}
@@ -0,0 +1,219 @@
+use crate::editor::Editor;
+use crate::notation::Move;
+use crate::puzzle::CurrentPuzzle;
+use crate::turn::TurnEvent;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use std::fmt::{Display, Formatter};
+
+#[cfg(debug_assertions)]
+use crate::camera::{CameraSettings, Mode};
+
+#[derive(Debug, Clone)]
+pub struct KeyboardPlugin {
+    keymap: Keymap,
+}
+
+impl KeyboardPlugin {
+    pub fn new(keymap: Keymap) -> Self {
+        Self { keymap }
+    }
+}
+
+impl Plugin for KeyboardPlugin {
+    fn build(&self, app: &mut App) {
+        for key in self.keymap.conflicts() {
+            warn!("{:?} is reserved and won't turn the puzzle", key);
+        }
+
+        app.insert_resource(self.keymap.clone())
+            .add_system(turn_with_keyboard);
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum KeymapPreset {
+    /// The layout of csTimer and most other online simulators, e.g. `J` = `U` and `F` = `U'`.
+    CsTimer,
+    /// Each key turns the move with the same letter, holding shift turns counterclockwise.
+    Letters,
+}
+
+#[derive(Debug, Clone, Default, Resource)]
+pub struct Keymap {
+    bindings: HashMap<KeyCode, Move>,
+    /// Whether holding shift turns the inverse of the bound move.
+    pub invert_with_shift: bool,
+}
+
+impl Keymap {
+    pub fn from_preset(preset: KeymapPreset) -> Self {
+        let (bindings, invert_with_shift): (&[(KeyCode, &str)], bool) = match preset {
+            KeymapPreset::CsTimer => (
+                &[
+                    (KeyCode::I, "R"),
+                    (KeyCode::K, "R'"),
+                    (KeyCode::W, "B"),
+                    (KeyCode::O, "B'"),
+                    (KeyCode::S, "D"),
+                    (KeyCode::L, "D'"),
+                    (KeyCode::D, "L"),
+                    (KeyCode::E, "L'"),
+                    (KeyCode::J, "U"),
+                    (KeyCode::F, "U'"),
+                    (KeyCode::H, "F"),
+                    (KeyCode::G, "F'"),
+                    (KeyCode::Semicolon, "y"),
+                    (KeyCode::A, "y'"),
+                    (KeyCode::U, "r"),
+                    (KeyCode::R, "l'"),
+                    (KeyCode::M, "r'"),
+                    (KeyCode::V, "l"),
+                    (KeyCode::T, "x"),
+                    (KeyCode::Y, "x"),
+                    (KeyCode::N, "x'"),
+                    (KeyCode::B, "x'"),
+                    (KeyCode::Period, "M'"),
+                    (KeyCode::X, "M'"),
+                    (KeyCode::Key5, "M"),
+                    (KeyCode::Key6, "M"),
+                    (KeyCode::P, "z"),
+                    (KeyCode::Q, "z'"),
+                    (KeyCode::Z, "d"),
+                    (KeyCode::Slash, "d'"),
+                    (KeyCode::Comma, "u"),
+                    (KeyCode::C, "u'"),
+                ],
+                false,
+            ),
+            KeymapPreset::Letters => (
+                &[
+                    (KeyCode::R, "R"),
+                    (KeyCode::L, "L"),
+                    (KeyCode::U, "U"),
+                    (KeyCode::D, "D"),
+                    (KeyCode::F, "F"),
+                    (KeyCode::B, "B"),
+                    (KeyCode::M, "M"),
+                    (KeyCode::E, "E"),
+                    (KeyCode::S, "S"),
+                    (KeyCode::X, "x"),
+                    (KeyCode::Y, "y"),
+                    (KeyCode::Z, "z"),
+                ],
+                true,
+            ),
+        };
+
+        Self {
+            bindings: bindings
+                .iter()
+                .map(|(key, m)| (*key, m.parse().unwrap()))
+                .collect(),
+            invert_with_shift,
+        }
+    }
+
+    /// Binds the move to the key and returns the move which was bound to it before.
+    pub fn bind(&mut self, key: KeyCode, m: Move) -> Result<Option<Move>, KeymapError> {
+        if is_reserved(key) {
+            return Err(KeymapError::Reserved(key));
+        }
+
+        Ok(self.bindings.insert(key, m))
+    }
+
+    pub fn unbind(&mut self, key: KeyCode) -> Option<Move> {
+        self.bindings.remove(&key)
+    }
+
+    pub fn get(&self, key: KeyCode) -> Option<Move> {
+        self.bindings.get(&key).copied()
+    }
+
+    /// Bound keys which are ignored, as the timer key and the `RESERVED_KEYS` of the camera, the
+    /// selection, the player and the editor must not turn the puzzle.
+    pub fn conflicts(&self) -> Vec<KeyCode> {
+        self.bindings
+            .keys()
+            .copied()
+            .filter(|key| is_reserved(*key))
+            .collect()
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum KeymapError {
+    Reserved(KeyCode),
+}
+
+impl Display for KeymapError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeymapError::Reserved(key) => {
+                write!(
+                    f,
+                    "{:?} is reserved for the camera, the timer, the puzzle selection, the player or the editor",
+                    key
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for KeymapError {}
+
+fn is_reserved(key: KeyCode) -> bool {
+    #[cfg(debug_assertions)]
+    if crate::camera::RESERVED_KEYS.contains(&key) {
+        return true;
+    }
+
+    key == crate::timer::TIMER_KEY
+        || crate::selection::RESERVED_KEYS.contains(&key)
+        || crate::player::RESERVED_KEYS.contains(&key)
+        || crate::editor::RESERVED_KEYS.contains(&key)
+}
+
+fn turn_with_keyboard(
+    keymap: Res<Keymap>,
+    keyboard_input: Res<Input<KeyCode>>,
+    puzzle: Option<Res<CurrentPuzzle>>,
+    editor: Res<Editor>,
+    mut turn_events: EventWriter<TurnEvent>,
+    #[cfg(debug_assertions)] camera_settings: Res<CameraSettings>,
+) {
+    #[cfg(debug_assertions)]
+    if camera_settings.mode == Mode::Flying {
+        return;
+    }
+
+    let Some(puzzle) = puzzle else {
+        return;
+    };
+
+    // shortcuts like undo and the colors to paint with share their keys with turns
+    if editor.is_painting() || keyboard_input.any_pressed([KeyCode::LControl, KeyCode::RControl]) {
+        return;
+    }
+
+    let invert =
+        keymap.invert_with_shift && keyboard_input.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+
+    for key in keyboard_input.get_just_pressed() {
+        if is_reserved(*key) {
+            continue;
+        }
+
+        let Some(m) = keymap.get(*key) else {
+            continue;
+        };
+
+        let m = if invert { m.inverse() } else { m };
+
+        // moves which the puzzle does not understand are ignored, e.g. slices on a pyraminx
+        if let Ok(turns) = puzzle.0.parse_turns(&m.to_string()) {
+            turn_events.send_batch(turns.into_iter().map(TurnEvent));
+        }
+    }
+}
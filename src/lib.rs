@@ -0,0 +1,34 @@
+//! Puzzle models, notation, scrambles, solvers and solve statistics, which build without Bevy
+//! when the default `render` feature is turned off. The feature adds the plugins the app is made
+//! of.
+
+pub mod notation;
+pub mod puzzle;
+pub mod scramble;
+pub mod solver;
+pub mod statistics;
+
+#[cfg(feature = "render")]
+pub mod camera;
+#[cfg(feature = "render")]
+pub mod color;
+#[cfg(feature = "render")]
+pub mod editor;
+#[cfg(feature = "render")]
+pub mod history;
+#[cfg(feature = "render")]
+pub mod keyboard;
+#[cfg(feature = "render")]
+pub mod picking;
+#[cfg(feature = "render")]
+pub mod player;
+#[cfg(feature = "render")]
+pub mod selection;
+#[cfg(feature = "render")]
+pub mod session;
+#[cfg(feature = "render")]
+pub mod timer;
+#[cfg(feature = "render")]
+pub mod turn;
+#[cfg(feature = "render")]
+pub mod view;
@@ -1,47 +1,41 @@
-mod camera;
-mod color;
-mod puzzle;
-mod view;
-
-use crate::camera::{CameraPlugin, CameraSettings};
-use crate::puzzle::Puzzle;
-use crate::view::{View, ViewPlugin};
 use bevy::prelude::*;
+use rubiks_cube_rs::camera::{CameraPlugin, CameraSettings};
+use rubiks_cube_rs::editor::EditorPlugin;
+use rubiks_cube_rs::history::HistoryPlugin;
+use rubiks_cube_rs::keyboard::{KeyboardPlugin, Keymap, KeymapPreset};
+use rubiks_cube_rs::picking::PickingPlugin;
+use rubiks_cube_rs::player::PlayerPlugin;
+use rubiks_cube_rs::puzzle::PuzzleKind;
+use rubiks_cube_rs::selection::{ActivePuzzle, SelectionPlugin};
+use rubiks_cube_rs::session::{self, SessionPlugin};
+use rubiks_cube_rs::timer::TimerPlugin;
+use rubiks_cube_rs::turn::TurnPlugin;
+use rubiks_cube_rs::view::ViewPlugin;
 
 fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
     App::new()
         .add_plugins(DefaultPlugins)
         .add_plugin(CameraPlugin::new(CameraSettings::default()))
         .add_plugin(ViewPlugin)
+        .add_plugin(TurnPlugin)
+        .add_plugin(HistoryPlugin)
+        .add_plugin(PickingPlugin)
+        .add_plugin(EditorPlugin)
+        .add_plugin(TimerPlugin)
+        .add_plugin(KeyboardPlugin::new(Keymap::from_preset(
+            KeymapPreset::CsTimer,
+        )))
+        .add_plugin(SelectionPlugin::new(ActivePuzzle::new(PuzzleKind::Rubik)))
+        // the algorithm to play can be passed as the first argument
+        .add_plugin(PlayerPlugin::new(args.first().cloned().unwrap_or_default()))
+        .add_plugin(SessionPlugin::new(session::DEFAULT_FILE_NAME.into()))
         .add_startup_system_to_stage(StartupStage::PostStartup, setup)
         .run();
 }
 
-fn setup(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    mut images: ResMut<Assets<Image>>,
-    view_query: Query<Entity, With<View>>,
-) {
-    let puzzle: Box<dyn Puzzle> = Box::new(puzzle::rubiks::Rubik::new(3));
-
-    let texture = images.add(puzzle.create_texture());
-    let material = materials.add(puzzle.create_material(texture));
-
-    let view_entity = view_query.single();
-
-    commands.entity(view_entity).add_children(|builder| {
-        for (mesh, transform) in puzzle.create_meshes() {
-            builder.spawn(PbrBundle {
-                transform,
-                mesh: meshes.add(mesh),
-                material: material.clone(),
-                ..default()
-            });
-        }
-    });
-
+fn setup(mut commands: Commands) {
     commands.spawn(DirectionalLightBundle {
         directional_light: DirectionalLight {
             illuminance: 50_000.0,
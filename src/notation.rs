@@ -0,0 +1,970 @@
+use crate::puzzle::rubiks::state::{Axis, Face, Turn};
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::str::FromStr;
+
+/// Byte range of the parsed input a node or an error belongs to.
+pub type Span = Range<usize>;
+
+/// Highest count a move or a group can be repeated with, like the `3` in `(R U)3`.
+pub const MAX_REPETITIONS: u32 = 1000;
+
+/// Most moves an algorithm can expand to.
+pub const MAX_MOVES: usize = 100_000;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Layers {
+    /// Only the n-th layer counted from the face, e.g. `R` or `2R`.
+    Single(u32),
+    /// The n outermost layers, e.g. `Rw`, `r` or `3Rw`.
+    Wide(u32),
+    /// All layers from the first to the second one, e.g. `2-3Rw`.
+    Range(u32, u32),
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Slice {
+    /// All inner layers turning like `L`.
+    M,
+    /// All inner layers turning like `D`.
+    E,
+    /// All inner layers turning like `F`.
+    S,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum MoveKind {
+    Face(Face, Layers),
+    Slice(Slice),
+    Rotation(Axis),
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Move {
+    pub kind: MoveKind,
+    /// Clockwise quarter turns, negative for counterclockwise ones.
+    pub amount: i32,
+}
+
+impl Move {
+    pub fn inverse(&self) -> Self {
+        Self {
+            kind: self.kind,
+            amount: -self.amount,
+        }
+    }
+
+    /// Converts the move into a turn of a cube with the given dimension or returns `None` if it
+    /// refers to layers the cube does not have.
+    pub fn to_turn(&self, dimension: u32) -> Option<Turn> {
+        match self.kind {
+            MoveKind::Face(face, layers) => {
+                let (first, last) = match layers {
+                    Layers::Single(layer) => (layer, layer),
+                    Layers::Wide(depth) => (1, depth),
+                    Layers::Range(first, last) => (first, last),
+                };
+
+                if first == 0 || first > last || last > dimension {
+                    return None;
+                }
+
+                Some(Turn::from_face(
+                    face,
+                    first - 1..=last - 1,
+                    self.amount,
+                    dimension,
+                ))
+            }
+            MoveKind::Slice(slice) => {
+                if dimension < 3 {
+                    return None;
+                }
+
+                let face = match slice {
+                    Slice::M => Face::Left,
+                    Slice::E => Face::Bottom,
+                    Slice::S => Face::Front,
+                };
+
+                Some(Turn::from_face(
+                    face,
+                    1..=dimension - 2,
+                    self.amount,
+                    dimension,
+                ))
+            }
+            MoveKind::Rotation(axis) => Some(Turn::rotation(axis, self.amount, dimension)),
+        }
+    }
+
+    /// Writes the turn of a cube with the given dimension as a move, counting the layers from
+    /// the closer face.
+    pub fn from_turn(turn: &Turn, dimension: u32) -> Self {
+        let last = dimension - 1;
+        let (first, end) = (*turn.layers.start(), *turn.layers.end());
+
+        let amount = match turn.quarter_turns.rem_euclid(4) {
+            3 => -1,
+            amount => amount,
+        };
+
+        let face = match turn.axis {
+            Axis::X => Face::Right,
+            Axis::Y => Face::Top,
+            Axis::Z => Face::Front,
+        };
+
+        if first == 0 && end == last {
+            return Self {
+                kind: MoveKind::Rotation(turn.axis),
+                amount,
+            };
+        }
+
+        if dimension >= 3 && first == 1 && end == last - 1 {
+            // M and E turn like the negative faces L and D, S like the positive face F
+            let (slice, amount) = match turn.axis {
+                Axis::X => (Slice::M, -amount),
+                Axis::Y => (Slice::E, -amount),
+                Axis::Z => (Slice::S, amount),
+            };
+
+            return Self {
+                kind: MoveKind::Slice(slice),
+                amount,
+            };
+        }
+
+        let (face, first, end, amount) = if first <= last - end {
+            (face, first + 1, end + 1, amount)
+        } else {
+            (face.opposite(), last - end + 1, last - first + 1, -amount)
+        };
+
+        let layers = match (first, end) {
+            (first, end) if first == end => Layers::Single(first),
+            (1, depth) => Layers::Wide(depth),
+            (first, end) => Layers::Range(first, end),
+        };
+
+        Self {
+            kind: MoveKind::Face(face, layers),
+            amount,
+        }
+    }
+}
+
+impl FromStr for Move {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let algorithm = s.parse::<Algorithm>()?;
+
+        match algorithm.0.as_slice() {
+            [Node::Move(m, _)] => Ok(*m),
+            _ => Err(NotationError {
+                kind: NotationErrorKind::ExpectedSingleMove,
+                span: 0..s.len(),
+            }),
+        }
+    }
+}
+
+/// Merges consecutive moves of the same kind and drops the ones cancelling out, so that `R R`
+/// becomes `R2` and `R U U' R'` disappears.
+pub fn merge_moves(moves: &[Move]) -> Vec<Move> {
+    let mut merged: Vec<Move> = Vec::with_capacity(moves.len());
+
+    for m in moves {
+        match merged.last_mut() {
+            Some(last) if last.kind == m.kind => match (last.amount + m.amount).rem_euclid(4) {
+                0 => {
+                    merged.pop();
+                }
+                3 => last.amount = -1,
+                amount => last.amount = amount,
+            },
+            _ => merged.push(*m),
+        }
+    }
+
+    merged
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Node {
+    Move(Move, Span),
+    /// `(A)n`
+    Group {
+        algorithm: Algorithm,
+        amount: i32,
+    },
+    /// `[A, B]n`, which is `A B A' B'`
+    Commutator {
+        first: Algorithm,
+        second: Algorithm,
+        amount: i32,
+    },
+    /// `[A: B]n`, which is `A B A'`
+    Conjugate {
+        setup: Algorithm,
+        algorithm: Algorithm,
+        amount: i32,
+    },
+}
+
+impl Node {
+    /// Number of moves the node expands to.
+    fn len(&self) -> usize {
+        let (body, amount) = match self {
+            Node::Move(..) => return 1,
+            Node::Group { algorithm, amount } => (algorithm.len(), amount),
+            Node::Commutator {
+                first,
+                second,
+                amount,
+            } => (
+                first.len().saturating_add(second.len()).saturating_mul(2),
+                amount,
+            ),
+            Node::Conjugate {
+                setup,
+                algorithm,
+                amount,
+            } => (
+                setup
+                    .len()
+                    .saturating_mul(2)
+                    .saturating_add(algorithm.len()),
+                amount,
+            ),
+        };
+
+        body.saturating_mul(amount.unsigned_abs() as usize)
+    }
+
+    fn push_moves(&self, moves: &mut Vec<(Move, Span)>) {
+        let (body, amount) = match self {
+            Node::Move(m, span) => {
+                moves.push((*m, span.clone()));
+                return;
+            }
+            Node::Group { algorithm, amount } => (algorithm.moves(), *amount),
+            Node::Commutator {
+                first,
+                second,
+                amount,
+            } => {
+                let first = first.moves();
+                let second = second.moves();
+                let body = [
+                    first.clone(),
+                    second.clone(),
+                    invert(&first),
+                    invert(&second),
+                ]
+                .concat();
+                (body, *amount)
+            }
+            Node::Conjugate {
+                setup,
+                algorithm,
+                amount,
+            } => {
+                let setup = setup.moves();
+                let body = [setup.clone(), algorithm.moves(), invert(&setup)].concat();
+                (body, *amount)
+            }
+        };
+
+        let body = if amount < 0 { invert(&body) } else { body };
+
+        for _ in 0..amount.unsigned_abs() {
+            moves.extend(body.iter().cloned());
+        }
+    }
+}
+
+fn invert(moves: &[(Move, Span)]) -> Vec<(Move, Span)> {
+    moves
+        .iter()
+        .rev()
+        .map(|(m, span)| (m.inverse(), span.clone()))
+        .collect()
+}
+
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Algorithm(pub Vec<Node>);
+
+impl Algorithm {
+    /// Number of moves with brackets and repetitions expanded, which is at most [`MAX_MOVES`]
+    /// for parsed algorithms.
+    fn len(&self) -> usize {
+        self.0
+            .iter()
+            .fold(0, |length, node| length.saturating_add(node.len()))
+    }
+
+    /// All moves with brackets and repetitions expanded, together with the span of the move
+    /// they originate from.
+    pub fn moves(&self) -> Vec<(Move, Span)> {
+        let mut moves = Vec::new();
+
+        for node in &self.0 {
+            node.push_moves(&mut moves);
+        }
+
+        moves
+    }
+
+    pub fn to_turns(&self, dimension: u32) -> Result<Vec<Turn>, NotationError> {
+        let turns = self.to_spanned_turns(dimension)?;
+        Ok(turns.into_iter().map(|(turn, _)| turn).collect())
+    }
+
+    /// Like [`Algorithm::to_turns`], but keeps the span of the move each turn originates from.
+    pub fn to_spanned_turns(&self, dimension: u32) -> Result<Vec<(Turn, Span)>, NotationError> {
+        self.moves()
+            .into_iter()
+            .map(|(m, span)| match m.to_turn(dimension) {
+                Some(turn) => Ok((turn, span)),
+                None => Err(NotationError {
+                    kind: NotationErrorKind::LayerOutOfRange { dimension },
+                    span,
+                }),
+            })
+            .collect()
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            input: s,
+            position: 0,
+        };
+
+        let algorithm = parser.parse_sequence()?;
+
+        match parser.peek() {
+            None => Ok(algorithm),
+            Some(c) => Err(parser.unexpected(c)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum NotationErrorKind {
+    UnexpectedCharacter(char),
+    UnexpectedEnd,
+    UnclosedParenthesis,
+    UnclosedBracket,
+    MissingSeparator,
+    InvalidNumber,
+    InvalidLayers,
+    LayerOutOfRange { dimension: u32 },
+    ExpectedSingleMove,
+    TooManyRepetitions { max: u32 },
+    TooManyMoves { max: usize },
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct NotationError {
+    pub kind: NotationErrorKind,
+    pub span: Span,
+}
+
+impl NotationError {
+    /// Marks the span of the error below the input it originates from.
+    pub fn highlight(&self, input: &str) -> String {
+        highlight(input, &self.span)
+    }
+}
+
+/// Marks the span below the input, e.g. to point at an error or the current move.
+pub fn highlight(input: &str, span: &Span) -> String {
+    let start = input[..span.start].chars().count();
+    let length = input[span.clone()].chars().count().max(1);
+
+    format!("{}\n{}{}", input, " ".repeat(start), "^".repeat(length))
+}
+
+impl Display for NotationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            NotationErrorKind::UnexpectedCharacter(c) => write!(f, "unexpected character '{}'", c),
+            NotationErrorKind::UnexpectedEnd => write!(f, "unexpected end of algorithm"),
+            NotationErrorKind::UnclosedParenthesis => write!(f, "unclosed parenthesis"),
+            NotationErrorKind::UnclosedBracket => write!(f, "unclosed bracket"),
+            NotationErrorKind::MissingSeparator => {
+                write!(f, "expected ',' or ':' inside of brackets")
+            }
+            NotationErrorKind::InvalidNumber => write!(f, "invalid number"),
+            NotationErrorKind::InvalidLayers => write!(f, "invalid layers"),
+            NotationErrorKind::LayerOutOfRange { dimension } => {
+                write!(f, "move does not exist on a {0}x{0}x{0} cube", dimension)
+            }
+            NotationErrorKind::ExpectedSingleMove => write!(f, "expected a single move"),
+            NotationErrorKind::TooManyRepetitions { max } => {
+                write!(f, "can't repeat more than {} times", max)
+            }
+            NotationErrorKind::TooManyMoves { max } => {
+                write!(f, "algorithm has more than {} moves", max)
+            }
+        }?;
+
+        write!(f, " at {}..{}", self.span.start, self.span.end)
+    }
+}
+
+impl std::error::Error for NotationError {}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    fn error(&self, kind: NotationErrorKind, span: Span) -> NotationError {
+        NotationError { kind, span }
+    }
+
+    fn unexpected(&self, c: char) -> NotationError {
+        self.error(
+            NotationErrorKind::UnexpectedCharacter(c),
+            self.position..self.position + c.len_utf8(),
+        )
+    }
+
+    fn parse_sequence(&mut self) -> Result<Algorithm, NotationError> {
+        let mut nodes = Vec::new();
+        let mut length: usize = 0;
+
+        loop {
+            self.skip_whitespace();
+            let start = self.position;
+
+            let node = match self.peek() {
+                None | Some(')' | ']' | ',' | ':') => break,
+                Some('(') => self.parse_group()?,
+                Some('[') => self.parse_brackets()?,
+                Some(_) => self.parse_move()?,
+            };
+
+            length = length.saturating_add(node.len());
+            self.check_length(length, start..self.position)?;
+            nodes.push(node);
+        }
+
+        Ok(Algorithm(nodes))
+    }
+
+    fn check_length(&self, length: usize, span: Span) -> Result<(), NotationError> {
+        if length > MAX_MOVES {
+            return Err(self.error(NotationErrorKind::TooManyMoves { max: MAX_MOVES }, span));
+        }
+
+        Ok(())
+    }
+
+    fn parse_group(&mut self) -> Result<Node, NotationError> {
+        let open = self.position..self.position + 1;
+        self.bump();
+
+        let algorithm = self.parse_sequence()?;
+
+        match self.peek() {
+            Some(')') => {
+                self.bump();
+            }
+            Some(c) => return Err(self.unexpected(c)),
+            None => return Err(self.error(NotationErrorKind::UnclosedParenthesis, open)),
+        }
+
+        let (amount, count) = self.parse_amount()?;
+        let node = Node::Group { algorithm, amount };
+        self.check_length(node.len(), count)?;
+
+        Ok(node)
+    }
+
+    fn parse_brackets(&mut self) -> Result<Node, NotationError> {
+        let open = self.position..self.position + 1;
+        self.bump();
+
+        let first = self.parse_sequence()?;
+
+        let is_commutator = match self.peek() {
+            Some(',') => true,
+            Some(':') => false,
+            Some(']') => {
+                return Err(self.error(
+                    NotationErrorKind::MissingSeparator,
+                    self.position..self.position + 1,
+                ))
+            }
+            Some(c) => return Err(self.unexpected(c)),
+            None => return Err(self.error(NotationErrorKind::UnclosedBracket, open)),
+        };
+        self.bump();
+
+        let second = self.parse_sequence()?;
+
+        match self.peek() {
+            Some(']') => {
+                self.bump();
+            }
+            Some(c) => return Err(self.unexpected(c)),
+            None => return Err(self.error(NotationErrorKind::UnclosedBracket, open)),
+        }
+
+        let (amount, count) = self.parse_amount()?;
+
+        let node = if is_commutator {
+            Node::Commutator {
+                first,
+                second,
+                amount,
+            }
+        } else {
+            Node::Conjugate {
+                setup: first,
+                algorithm: second,
+                amount,
+            }
+        };
+
+        // without a count, the moves of the brackets themselves are too many
+        let span = if count.is_empty() {
+            open.start..self.position
+        } else {
+            count
+        };
+        self.check_length(node.len(), span)?;
+
+        Ok(node)
+    }
+
+    fn parse_move(&mut self) -> Result<Node, NotationError> {
+        let start = self.position;
+
+        let prefix = match self.peek() {
+            Some(c) if c.is_ascii_digit() => {
+                let first = self.parse_number()?;
+
+                if self.peek() == Some('-') {
+                    self.bump();
+                    let last = self.parse_number()?;
+                    Some((first, Some(last)))
+                } else {
+                    Some((first, None))
+                }
+            }
+            _ => None,
+        };
+
+        let c = match self.peek() {
+            Some(c) => c,
+            None => return Err(self.error(NotationErrorKind::UnexpectedEnd, start..self.position)),
+        };
+
+        let kind = if let Some(face) = Face::from_letter(c.to_ascii_uppercase()) {
+            self.bump();
+
+            let is_wide = if c.is_ascii_lowercase() {
+                true
+            } else if self.peek() == Some('w') {
+                self.bump();
+                true
+            } else {
+                false
+            };
+
+            let layers = match (prefix, is_wide) {
+                (None, false) => Layers::Single(1),
+                (None, true) => Layers::Wide(2),
+                (Some((layer, None)), false) => Layers::Single(layer),
+                (Some((depth, None)), true) => Layers::Wide(depth),
+                (Some((first, Some(last))), _) => Layers::Range(first, last),
+            };
+
+            let is_valid = match layers {
+                Layers::Single(layer) => layer > 0,
+                Layers::Wide(depth) => depth > 0,
+                Layers::Range(first, last) => first > 0 && first <= last,
+            };
+
+            if !is_valid {
+                return Err(self.error(NotationErrorKind::InvalidLayers, start..self.position));
+            }
+
+            MoveKind::Face(face, layers)
+        } else {
+            let kind = match c {
+                'M' => MoveKind::Slice(Slice::M),
+                'E' => MoveKind::Slice(Slice::E),
+                'S' => MoveKind::Slice(Slice::S),
+                'x' => MoveKind::Rotation(Axis::X),
+                'y' => MoveKind::Rotation(Axis::Y),
+                'z' => MoveKind::Rotation(Axis::Z),
+                _ => return Err(self.unexpected(c)),
+            };
+            self.bump();
+
+            if prefix.is_some() {
+                return Err(self.error(NotationErrorKind::InvalidLayers, start..self.position));
+            }
+
+            kind
+        };
+
+        let (amount, _) = self.parse_amount()?;
+
+        Ok(Node::Move(Move { kind, amount }, start..self.position))
+    }
+
+    fn parse_number(&mut self) -> Result<u32, NotationError> {
+        let start = self.position;
+
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.bump();
+        }
+
+        if start == self.position {
+            return match self.peek() {
+                Some(c) => Err(self.unexpected(c)),
+                None => Err(self.error(NotationErrorKind::UnexpectedEnd, start..start)),
+            };
+        }
+
+        self.input[start..self.position]
+            .parse()
+            .map_err(|_| self.error(NotationErrorKind::InvalidNumber, start..self.position))
+    }
+
+    /// Parses an optional repetition count followed by an optional prime and returns the
+    /// amount together with the span of the count, which is empty if there is none.
+    fn parse_amount(&mut self) -> Result<(i32, Span), NotationError> {
+        let start = self.position;
+
+        let amount = match self.peek() {
+            Some(c) if c.is_ascii_digit() => {
+                let amount = self.parse_number()?;
+
+                if amount > MAX_REPETITIONS {
+                    return Err(self.error(
+                        NotationErrorKind::TooManyRepetitions {
+                            max: MAX_REPETITIONS,
+                        },
+                        start..self.position,
+                    ));
+                }
+
+                amount as i32
+            }
+            _ => 1,
+        };
+
+        let count = start..self.position;
+
+        if matches!(self.peek(), Some('\'' | '’' | '′')) {
+            self.bump();
+            Ok((-amount, count))
+        } else {
+            Ok((amount, count))
+        }
+    }
+}
+
+fn fmt_amount(f: &mut Formatter<'_>, amount: i32) -> std::fmt::Result {
+    if amount.abs() != 1 {
+        write!(f, "{}", amount.abs())?;
+    }
+
+    if amount < 0 {
+        write!(f, "'")?;
+    }
+
+    Ok(())
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            MoveKind::Face(face, layers) => {
+                let letter = face.letter();
+
+                match layers {
+                    Layers::Single(1) => write!(f, "{}", letter),
+                    Layers::Single(layer) => write!(f, "{}{}", layer, letter),
+                    Layers::Wide(2) => write!(f, "{}w", letter),
+                    Layers::Wide(depth) => write!(f, "{}{}w", depth, letter),
+                    Layers::Range(first, last) => write!(f, "{}-{}{}w", first, last, letter),
+                }
+            }
+            MoveKind::Slice(slice) => write!(f, "{:?}", slice),
+            MoveKind::Rotation(axis) => write!(f, "{}", format!("{:?}", axis).to_lowercase()),
+        }?;
+
+        fmt_amount(f, self.amount)
+    }
+}
+
+impl Display for Node {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Node::Move(m, _) => write!(f, "{}", m),
+            Node::Group { algorithm, amount } => {
+                write!(f, "({})", algorithm)?;
+                fmt_amount(f, *amount)
+            }
+            Node::Commutator {
+                first,
+                second,
+                amount,
+            } => {
+                write!(f, "[{}, {}]", first, second)?;
+                fmt_amount(f, *amount)
+            }
+            Node::Conjugate {
+                setup,
+                algorithm,
+                amount,
+            } => {
+                write!(f, "[{}: {}]", setup, algorithm)?;
+                fmt_amount(f, *amount)
+            }
+        }
+    }
+}
+
+impl Display for Algorithm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, node) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+
+            write!(f, "{}", node)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn moves(notation: &str) -> Vec<Move> {
+        let algorithm = notation.parse::<Algorithm>().unwrap();
+        algorithm.moves().into_iter().map(|(m, _)| m).collect()
+    }
+
+    fn single(notation: &str) -> Move {
+        notation.parse().unwrap()
+    }
+
+    fn face_move(face: Face, layers: Layers, amount: i32) -> Move {
+        Move {
+            kind: MoveKind::Face(face, layers),
+            amount,
+        }
+    }
+
+    fn error(notation: &str) -> (NotationErrorKind, Span) {
+        let error = notation.parse::<Algorithm>().unwrap_err();
+        (error.kind, error.span)
+    }
+
+    #[test]
+    fn parses_face_moves() {
+        assert_eq!(
+            moves("R U R' U'"),
+            [
+                face_move(Face::Right, Layers::Single(1), 1),
+                face_move(Face::Top, Layers::Single(1), 1),
+                face_move(Face::Right, Layers::Single(1), -1),
+                face_move(Face::Top, Layers::Single(1), -1),
+            ]
+        );
+        assert_eq!(
+            moves("2R Rw2 3Uw' r 2-3Fw B’"),
+            [
+                face_move(Face::Right, Layers::Single(2), 1),
+                face_move(Face::Right, Layers::Wide(2), 2),
+                face_move(Face::Top, Layers::Wide(3), -1),
+                face_move(Face::Right, Layers::Wide(2), 1),
+                face_move(Face::Front, Layers::Range(2, 3), 1),
+                face_move(Face::Back, Layers::Single(1), -1),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_slices_and_rotations() {
+        let kinds = moves("M E S x y z")
+            .into_iter()
+            .map(|m| m.kind)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            kinds,
+            [
+                MoveKind::Slice(Slice::M),
+                MoveKind::Slice(Slice::E),
+                MoveKind::Slice(Slice::S),
+                MoveKind::Rotation(Axis::X),
+                MoveKind::Rotation(Axis::Y),
+                MoveKind::Rotation(Axis::Z),
+            ]
+        );
+    }
+
+    #[test]
+    fn expands_groups_and_brackets() {
+        let r = single("R");
+        let u = single("U");
+        let d = single("D");
+
+        assert_eq!(moves("(R U)2"), [r, u, r, u]);
+        assert_eq!(moves("(R U)'"), [u.inverse(), r.inverse()]);
+        assert_eq!(moves("(R U)0"), []);
+        assert_eq!(moves("[R, U]"), [r, u, r.inverse(), u.inverse()]);
+        assert_eq!(moves("[R: U]"), [r, u, r.inverse()]);
+        assert_eq!(moves("[R, U]2").len(), 8);
+        assert_eq!(
+            moves("[R: [U, D]]"),
+            [r, u, d, u.inverse(), d.inverse(), r.inverse()]
+        );
+    }
+
+    #[test]
+    fn moves_keep_the_span_they_come_from() {
+        let spans = "R (U F’)2"
+            .parse::<Algorithm>()
+            .unwrap()
+            .moves()
+            .into_iter()
+            .map(|(_, span)| span)
+            .collect::<Vec<_>>();
+
+        assert_eq!(spans, [0..1, 3..4, 5..9, 3..4, 5..9]);
+    }
+
+    #[test]
+    fn errors_point_at_their_span() {
+        use NotationErrorKind::*;
+
+        assert_eq!(error("R Q"), (UnexpectedCharacter('Q'), 2..3));
+        assert_eq!(error("R 2"), (UnexpectedEnd, 2..3));
+        assert_eq!(error("(R U"), (UnclosedParenthesis, 0..1));
+        assert_eq!(error("R [U, D"), (UnclosedBracket, 2..3));
+        assert_eq!(error("[R U]"), (MissingSeparator, 4..5));
+        assert_eq!(error("99999999999R"), (InvalidNumber, 0..11));
+        assert_eq!(error("0R"), (InvalidLayers, 0..2));
+        assert_eq!(error("3-2R"), (InvalidLayers, 0..4));
+        assert_eq!(error("2M"), (InvalidLayers, 0..2));
+
+        let error = "R 4R"
+            .parse::<Algorithm>()
+            .unwrap()
+            .to_turns(3)
+            .unwrap_err();
+        assert_eq!(error.kind, LayerOutOfRange { dimension: 3 });
+        assert_eq!(error.span, 2..4);
+
+        let error = "R U".parse::<Move>().unwrap_err();
+        assert_eq!((error.kind, error.span), (ExpectedSingleMove, 0..3));
+    }
+
+    #[test]
+    fn repetitions_are_limited() {
+        use NotationErrorKind::*;
+
+        let too_many_repetitions = TooManyRepetitions {
+            max: MAX_REPETITIONS,
+        };
+        let too_many_moves = TooManyMoves { max: MAX_MOVES };
+
+        assert_eq!(error("(R U)1001"), (too_many_repetitions.clone(), 5..9));
+        assert_eq!(error("R999999999"), (too_many_repetitions, 1..10));
+        assert_eq!(error("((R U)1000)100"), (too_many_moves.clone(), 11..14));
+        assert_eq!(error("((R)1000)100 U"), (too_many_moves.clone(), 13..14));
+        assert_eq!(error("[((R)1000)50: U]"), (too_many_moves, 0..16));
+
+        assert_eq!(moves("((R)1000)100").len(), MAX_MOVES);
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let notations = [
+            "R U R' U'",
+            "2R Rw2 3Uw' 2-3Fw",
+            "M2 E' S x y2 z'",
+            "(R U)2' [R, U]2 [R: U'] [F: [R, U]]",
+        ];
+
+        for notation in notations {
+            let algorithm = notation.parse::<Algorithm>().unwrap();
+            assert_eq!(algorithm.to_string(), notation);
+        }
+
+        assert_eq!(single("r").to_string(), "Rw");
+    }
+
+    #[test]
+    fn turns_round_trip_through_moves() {
+        for dimension in 1..=6 {
+            for axis in [Axis::X, Axis::Y, Axis::Z] {
+                for first in 0..dimension {
+                    for last in first..dimension {
+                        for quarter_turns in [1, 2, -1] {
+                            let turn = Turn {
+                                axis,
+                                layers: first..=last,
+                                quarter_turns,
+                            };
+
+                            let m = Move::from_turn(&turn, dimension);
+                            assert_eq!(m.to_turn(dimension), Some(turn), "{}", m);
+                            assert_eq!(single(&m.to_string()), m);
+                        }
+                    }
+                }
+            }
+        }
+
+        assert_eq!(single("4R").to_turn(3), None);
+        assert_eq!(single("2-4Rw").to_turn(3), None);
+        assert_eq!(single("M").to_turn(2), None);
+    }
+
+    #[test]
+    fn merges_moves() {
+        assert_eq!(merge_moves(&moves("R R")), moves("R2"));
+        assert_eq!(merge_moves(&moves("R2 R")), moves("R'"));
+        assert_eq!(merge_moves(&moves("R U U' R'")), []);
+        assert_eq!(merge_moves(&moves("R L R")), moves("R L R"));
+        assert_eq!(merge_moves(&moves("R Rw 2R")), moves("R Rw 2R"));
+    }
+}
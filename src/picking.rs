@@ -0,0 +1,282 @@
+use crate::editor::{Editor, PaintEvent};
+use crate::puzzle::{CurrentPuzzle, PuzzleRender, PuzzleTurn};
+use crate::turn::{Piece, TurnEvent};
+use crate::view::{View, ViewLock};
+use bevy::prelude::*;
+use bevy::render::primitives::Aabb;
+
+pub struct PickingPlugin;
+
+impl Plugin for PickingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PointerState>()
+            .add_system(drag_to_turn.before("change_view"));
+    }
+}
+
+/// Pixels the pointer has to move before a drag turns a layer.
+const DRAG_THRESHOLD: f32 = 10.0;
+
+/// Axes which are closer to the normal of the grabbed side than this would spin the side
+/// instead of moving the grabbed sticker across the puzzle.
+const MAX_AXIS_ALIGNMENT: f32 = 0.9;
+
+#[derive(Debug, Default, Resource)]
+enum PointerState {
+    #[default]
+    Released,
+    Grabbed(Grab),
+    /// The drag already turned a layer or started on empty space.
+    Consumed,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Grab {
+    piece: usize,
+    /// Normal of the grabbed sticker in the local space of the view.
+    normal: Vec3,
+    /// Point on the grabbed sticker in world space.
+    point: Vec3,
+    start: Vec2,
+}
+
+struct Pointer {
+    position: Vec2,
+    just_pressed: bool,
+    pressed: bool,
+}
+
+fn get_pointer(
+    windows: &Windows,
+    mouse_button_input: &Input<MouseButton>,
+    touches: &Touches,
+) -> Option<Pointer> {
+    if let Some(touch) = touches.iter().next() {
+        return Some(Pointer {
+            position: touch.position(),
+            just_pressed: touches.just_pressed(touch.id()),
+            pressed: true,
+        });
+    }
+
+    let position = windows.get_primary()?.cursor_position()?;
+
+    Some(Pointer {
+        position,
+        just_pressed: mouse_button_input.just_pressed(MouseButton::Left),
+        pressed: mouse_button_input.pressed(MouseButton::Left),
+    })
+}
+
+fn drag_to_turn(
+    mut pointer_state: ResMut<PointerState>,
+    mut view_lock: ResMut<ViewLock>,
+    mut turn_events: EventWriter<TurnEvent>,
+    mut paint_events: EventWriter<PaintEvent>,
+    puzzle: Option<Res<CurrentPuzzle>>,
+    editor: Res<Editor>,
+    windows: Res<Windows>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    touches: Res<Touches>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    view_query: Query<&GlobalTransform, With<View>>,
+    piece_query: Query<(&Piece, &GlobalTransform, &Aabb)>,
+) {
+    let pointer = match get_pointer(&windows, &mouse_button_input, &touches) {
+        Some(pointer) if pointer.pressed => pointer,
+        _ => {
+            *pointer_state = PointerState::Released;
+            view_lock.0 = false;
+            return;
+        }
+    };
+
+    let Some(puzzle) = puzzle else {
+        return;
+    };
+
+    let (camera, camera_transform) = camera_query.single();
+    let view_transform = view_query.single();
+
+    if pointer.just_pressed || matches!(*pointer_state, PointerState::Released) {
+        let grab = camera
+            .viewport_to_world(camera_transform, pointer.position)
+            .and_then(|ray| pick_sticker(puzzle.0.as_ref(), ray, view_transform, &piece_query));
+
+        view_lock.0 = grab.is_some();
+
+        *pointer_state = match grab {
+            // while painting, clicking a sticker paints it instead of starting a drag
+            Some((piece, normal, _)) if editor.is_painting() => {
+                paint_events.send(PaintEvent { piece, normal });
+                PointerState::Consumed
+            }
+            Some((piece, normal, point)) => PointerState::Grabbed(Grab {
+                piece,
+                normal,
+                point,
+                start: pointer.position,
+            }),
+            None => PointerState::Consumed,
+        };
+
+        return;
+    }
+
+    let PointerState::Grabbed(grab) = *pointer_state else {
+        return;
+    };
+
+    let drag = pointer.position - grab.start;
+
+    if drag.length() < DRAG_THRESHOLD {
+        return;
+    }
+
+    if let Some(turn) = get_drag_turn(
+        puzzle.0.as_ref(),
+        &grab,
+        drag,
+        camera,
+        camera_transform,
+        view_transform,
+    ) {
+        turn_events.send(TurnEvent(turn));
+    }
+
+    *pointer_state = PointerState::Consumed;
+}
+
+/// Finds the closest piece hit by the ray and returns it together with the normal of the hit
+/// sticker in view space and the hit point in world space.
+fn pick_sticker(
+    puzzle: &dyn PuzzleRender,
+    ray: Ray,
+    view_transform: &GlobalTransform,
+    piece_query: &Query<(&Piece, &GlobalTransform, &Aabb)>,
+) -> Option<(usize, Vec3, Vec3)> {
+    // without spawned pieces the hit side of the box around the puzzle is the sticker
+    if let Some(chunks) = puzzle.chunks() {
+        let view_inverse = view_transform.affine().inverse();
+        let origin = view_inverse.transform_point3(ray.origin);
+        let direction = view_inverse.transform_vector3(ray.direction);
+
+        let (distance, normal) = intersect_aabb(origin, direction, &chunks.bounds())?;
+        let piece = chunks.piece_at(origin + direction * distance, normal)?;
+
+        return Some((piece, normal, ray.origin + ray.direction * distance));
+    }
+
+    let (piece, distance, local_normal, transform) = piece_query
+        .iter()
+        .filter_map(|(piece, transform, aabb)| {
+            let inverse = transform.affine().inverse();
+            let origin = inverse.transform_point3(ray.origin);
+            let direction = inverse.transform_vector3(ray.direction);
+
+            intersect_aabb(origin, direction, aabb)
+                .map(|(distance, normal)| (piece.0, distance, normal, transform))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))?;
+
+    let view_inverse = view_transform.affine().inverse();
+    let hit_normal =
+        view_inverse.transform_vector3(transform.affine().transform_vector3(local_normal));
+    let view_direction = view_inverse.transform_vector3(ray.direction);
+
+    // the bounding box only approximates the piece, so the hit side of it decides between the
+    // sides of the piece which carry a sticker and face the camera
+    let normal = puzzle
+        .sticker_normals(piece)
+        .into_iter()
+        .filter(|normal| normal.dot(view_direction) < 0.0)
+        .map(|normal| (normal, normal.dot(hit_normal)))
+        .filter(|(_, alignment)| *alignment > 0.0)
+        .max_by(|a, b| a.1.total_cmp(&b.1))?
+        .0;
+
+    Some((piece, normal, ray.origin + ray.direction * distance))
+}
+
+/// Slab test returning the distance along the ray and the normal of the entered side.
+fn intersect_aabb(origin: Vec3, direction: Vec3, aabb: &Aabb) -> Option<(f32, Vec3)> {
+    let min = Vec3::from(aabb.min());
+    let max = Vec3::from(aabb.max());
+
+    let mut entry = f32::NEG_INFINITY;
+    let mut exit = f32::INFINITY;
+    let mut normal = Vec3::ZERO;
+
+    for axis in 0..3 {
+        if direction[axis].abs() < f32::EPSILON {
+            if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                return None;
+            }
+
+            continue;
+        }
+
+        let t1 = (min[axis] - origin[axis]) / direction[axis];
+        let t2 = (max[axis] - origin[axis]) / direction[axis];
+
+        if t1.min(t2) > entry {
+            entry = t1.min(t2);
+            normal = Vec3::ZERO;
+            normal[axis] = -direction[axis].signum();
+        }
+
+        exit = exit.min(t1.max(t2));
+    }
+
+    (entry <= exit && entry >= 0.0).then_some((entry, normal))
+}
+
+/// Picks the turn which moves the grabbed sticker most like the drag on screen, turning the
+/// layer of the grabbed piece around one of the axes of the puzzle.
+fn get_drag_turn(
+    puzzle: &dyn PuzzleRender,
+    grab: &Grab,
+    drag: Vec2,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    view_transform: &GlobalTransform,
+) -> Option<PuzzleTurn> {
+    let start = camera.world_to_viewport(camera_transform, grab.point)?;
+    let offset = view_transform
+        .affine()
+        .inverse()
+        .transform_point3(grab.point)
+        - puzzle.pivot();
+
+    let (axis, amount, _) = puzzle
+        .axes()
+        .into_iter()
+        .enumerate()
+        .filter(|(_, axis)| axis.dot(grab.normal).abs() < MAX_AXIS_ALIGNMENT)
+        .flat_map(|(index, axis)| [(index, axis, 1), (index, axis, -1)])
+        .filter_map(|(index, axis, amount)| {
+            // positive amounts turn clockwise, which is a negative rotation around the axis
+            let movement = -(amount as f32) * axis.cross(offset);
+            let direction = movement
+                .reject_from_normalized(grab.normal)
+                .normalize_or_zero();
+
+            let world_direction = view_transform.affine().transform_vector3(direction);
+            let end = camera.world_to_viewport(camera_transform, grab.point + world_direction)?;
+
+            Some((
+                index,
+                amount,
+                (end - start).normalize_or_zero().dot(drag.normalize()),
+            ))
+        })
+        .max_by(|a, b| a.2.total_cmp(&b.2))?;
+
+    let layer = puzzle.piece_layer(grab.piece, axis);
+
+    Some(PuzzleTurn {
+        axis,
+        layers: layer..=layer,
+        amount,
+    })
+}
@@ -0,0 +1,230 @@
+use crate::editor::Editor;
+use crate::notation::{highlight, NotationError, Span};
+use crate::puzzle::{CurrentPuzzle, PuzzleTurn};
+use crate::selection::ActivePuzzle;
+use crate::turn::{TurnEvent, TurnQueue};
+use bevy::prelude::*;
+use std::ops::Range;
+
+const PLAY_KEY: KeyCode = KeyCode::Return;
+const FORWARD_KEY: KeyCode = KeyCode::Right;
+const BACK_KEY: KeyCode = KeyCode::Left;
+const START_KEY: KeyCode = KeyCode::Home;
+const END_KEY: KeyCode = KeyCode::End;
+const FASTER_KEY: KeyCode = KeyCode::Up;
+const SLOWER_KEY: KeyCode = KeyCode::Down;
+
+/// Keys controlling the player.
+pub const RESERVED_KEYS: [KeyCode; 7] = [
+    PLAY_KEY,
+    FORWARD_KEY,
+    BACK_KEY,
+    START_KEY,
+    END_KEY,
+    FASTER_KEY,
+    SLOWER_KEY,
+];
+
+const MIN_SPEED: f32 = 0.25;
+const MAX_SPEED: f32 = 16.0;
+const SPEED_FACTOR: f32 = 1.5;
+
+/// Plays the algorithm it is created with.
+///
+/// The app draws no text, so like the solve times of the timer, the progress only goes to the
+/// log: after every move the algorithm gets logged with the move played last marked below it.
+pub struct PlayerPlugin {
+    algorithm: String,
+}
+
+impl PlayerPlugin {
+    pub fn new(algorithm: String) -> Self {
+        Self { algorithm }
+    }
+}
+
+impl Plugin for PlayerPlugin {
+    fn build(&self, app: &mut App) {
+        let mut player = Player::default();
+        player.load(self.algorithm.clone());
+
+        app.insert_resource(player)
+            .add_system(run_player.after("spawn_puzzle").before("queue_turns"));
+    }
+}
+
+/// Plays an algorithm move by move through the [`TurnQueue`], starting from the state the puzzle
+/// is in when the algorithm gets loaded.
+#[derive(Debug, Resource)]
+pub struct Player {
+    algorithm: String,
+    /// The algorithm parsed for the current puzzle, or `None` until it got parsed for it.
+    turns: Option<Result<Vec<(PuzzleTurn, Span)>, NotationError>>,
+    /// Number of moves of the algorithm which are applied.
+    position: usize,
+    playing: bool,
+    /// Moves per second while playing.
+    pub speed: f32,
+    /// Seconds since the last move was played.
+    elapsed: f32,
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Self {
+            algorithm: String::new(),
+            turns: None,
+            position: 0,
+            playing: false,
+            speed: 2.0,
+            elapsed: 0.0,
+        }
+    }
+}
+
+impl Player {
+    /// Replaces the algorithm, which gets parsed once for each puzzle it is played on.
+    pub fn load(&mut self, algorithm: String) {
+        self.algorithm = algorithm;
+        self.turns = None;
+        self.rewind();
+    }
+
+    /// Forgets how far the algorithm got played, e.g. because the puzzle got replaced.
+    pub fn rewind(&mut self) {
+        self.position = 0;
+        self.playing = false;
+        self.elapsed = 0.0;
+    }
+}
+
+fn run_player(
+    mut player: ResMut<Player>,
+    mut turn_queue: ResMut<TurnQueue>,
+    mut turn_events: EventWriter<TurnEvent>,
+    puzzle: Option<Res<CurrentPuzzle>>,
+    active_puzzle: Res<ActivePuzzle>,
+    editor: Res<Editor>,
+    keyboard_input: Res<Input<KeyCode>>,
+    time: Res<Time>,
+) {
+    // the new puzzle is solved, but only gets spawned at the end of this frame
+    if active_puzzle.is_changed() {
+        player.turns = None;
+        player.rewind();
+        return;
+    }
+
+    let Some(puzzle) = puzzle else {
+        return;
+    };
+
+    // the player keeps its position, as painting doesn't turn anything
+    if editor.is_painting() {
+        return;
+    }
+
+    if player.algorithm.trim().is_empty() {
+        return;
+    }
+
+    let player = &mut *player;
+    let parsed = player
+        .turns
+        .get_or_insert_with(|| puzzle.0.parse_algorithm(&player.algorithm));
+
+    let turns = match parsed {
+        Ok(turns) => turns,
+        Err(error) => {
+            if keyboard_input.any_just_pressed(RESERVED_KEYS) {
+                warn!("{}\n{}", error, error.highlight(&player.algorithm));
+            }
+
+            return;
+        }
+    };
+
+    let position = player.position;
+
+    if keyboard_input.just_pressed(FASTER_KEY) {
+        player.speed = (player.speed * SPEED_FACTOR).min(MAX_SPEED);
+        info!("playing {} moves per second", player.speed);
+    }
+
+    if keyboard_input.just_pressed(SLOWER_KEY) {
+        player.speed = (player.speed / SPEED_FACTOR).max(MIN_SPEED);
+        info!("playing {} moves per second", player.speed);
+    }
+
+    let forward = |range: Range<usize>| turns[range].iter().map(|(turn, _)| turn.clone());
+    let back = |range: Range<usize>| turns[range].iter().rev().map(|(turn, _)| turn.inverse());
+
+    if keyboard_input.just_pressed(PLAY_KEY) {
+        // playing once more from the end starts over
+        if !player.playing && position == turns.len() {
+            jump(&mut turn_events, &mut turn_queue, back(0..position));
+            player.position = 0;
+        }
+
+        player.playing = !player.playing;
+        player.elapsed = 0.0;
+    } else if keyboard_input.just_pressed(FORWARD_KEY) && position < turns.len() {
+        player.playing = false;
+        turn_events.send_batch(forward(position..position + 1).map(TurnEvent));
+        player.position += 1;
+    } else if keyboard_input.just_pressed(BACK_KEY) && position > 0 {
+        player.playing = false;
+        turn_events.send_batch(back(position - 1..position).map(TurnEvent));
+        player.position -= 1;
+    } else if keyboard_input.just_pressed(START_KEY) {
+        player.playing = false;
+        jump(&mut turn_events, &mut turn_queue, back(0..position));
+        player.position = 0;
+    } else if keyboard_input.just_pressed(END_KEY) {
+        player.playing = false;
+        jump(
+            &mut turn_events,
+            &mut turn_queue,
+            forward(position..turns.len()),
+        );
+        player.position = turns.len();
+    } else if player.playing {
+        player.elapsed += time.delta_seconds();
+
+        // the next move waits for the previous one, no matter how fast the player is
+        if turn_queue.is_idle() && player.elapsed >= 1.0 / player.speed {
+            turn_events.send_batch(forward(position..position + 1).map(TurnEvent));
+            player.position += 1;
+            player.elapsed = 0.0;
+        }
+    }
+
+    if player.position == turns.len() {
+        player.playing = false;
+    }
+
+    if player.position == position {
+        return;
+    }
+
+    // the log is where the progress shows up, as there is no text on screen
+    match player.position.checked_sub(1) {
+        Some(last) => info!(
+            "move {}/{}\n{}",
+            player.position,
+            turns.len(),
+            highlight(&player.algorithm, &turns[last].1)
+        ),
+        None => info!("move 0/{}\n{}", turns.len(), player.algorithm),
+    }
+}
+
+/// Turns all moves at once instead of animating them one by one.
+fn jump(
+    turn_events: &mut EventWriter<TurnEvent>,
+    turn_queue: &mut TurnQueue,
+    turns: impl Iterator<Item = PuzzleTurn>,
+) {
+    turn_events.send_batch(turns.map(TurnEvent));
+    turn_queue.skip_animations();
+}
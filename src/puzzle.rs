@@ -1,15 +1,312 @@
-use crate::StandardMaterial;
-use bevy::prelude::{Handle, Image, Mesh, Transform};
+use crate::notation::{NotationError, Span};
+use crate::puzzle::mirror::state::MirrorState;
+use crate::puzzle::pyraminx::state::{PyraminxState, PyraminxTurn, Vertex};
+use crate::puzzle::rubiks::state::{Axis, RubikState, Turn};
+use crate::scramble::Scramble;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
 
 pub mod mirror;
 pub mod pyraminx;
 pub mod rubiks;
 
-const TOTAL_SIDE_LENGTH: f32 = 1.0;
-const GAP_SIZE: f32 = 0.005;
+#[cfg(feature = "render")]
+mod render;
 
-pub trait Puzzle {
-    fn create_texture(&self) -> Image;
-    fn create_material(&self, texture: Handle<Image>) -> StandardMaterial;
-    fn create_meshes(&self) -> Vec<(Mesh, Transform)>;
+#[cfg(feature = "render")]
+pub use render::{Chunks, ColorSchemes, CurrentPuzzle, Paint, PuzzleRender, Styles};
+
+/// The rules of a puzzle, which the app, the command line and the scrambles share, so that
+/// turning, the notation and scrambling work the same for all of them.
+///
+/// Axes are referred to by their index below [`Puzzle::axis_count`] and pieces by an index which
+/// stays the same while they move around.
+pub trait Puzzle: Send + Sync + 'static {
+    fn axis_count(&self) -> usize;
+
+    /// Number of layers along the axis with the given index.
+    fn layer_count(&self, axis: usize) -> u32;
+
+    /// Number of steps of [`PuzzleTurn::amount`] which turn a layer all the way around.
+    fn steps_per_revolution(&self) -> i32;
+
+    /// Turns of single layers, which every state of the puzzle can be reached with.
+    fn legal_turns(&self) -> Vec<PuzzleTurn>;
+
+    /// Parses moves in the notation of the puzzle together with the span of the move each turn
+    /// originates from.
+    fn parse_algorithm(&self, notation: &str) -> Result<Vec<(PuzzleTurn, Span)>, NotationError>;
+
+    /// Parses moves in the notation of the puzzle.
+    fn parse_turns(&self, notation: &str) -> Result<Vec<PuzzleTurn>, NotationError> {
+        let turns = self.parse_algorithm(notation)?;
+        Ok(turns.into_iter().map(|(turn, _)| turn).collect())
+    }
+
+    /// Writes the turn in the notation of the puzzle.
+    fn format_turn(&self, turn: &PuzzleTurn) -> String;
+
+    fn turn(&mut self, turn: &PuzzleTurn);
+
+    /// Indices of all pieces which are currently in the given layers.
+    fn pieces_in_layers(&self, axis: usize, layers: RangeInclusive<u32>) -> Vec<usize>;
+
+    /// Layer of the given axis the piece with the given index currently lies in.
+    fn piece_layer(&self, piece: usize, axis: usize) -> u32;
+
+    fn is_solved(&self) -> bool;
+
+    /// Turns which mix up the solved puzzle and can be reproduced with the seed. Puzzles without
+    /// a scramble of their own get [`Scramble::random_turns`] of 20 turns for every layer
+    /// beyond the second of their biggest axis.
+    fn scramble(&self, seed: u64) -> Scramble<PuzzleTurn> {
+        let layers = (0..self.axis_count())
+            .map(|axis| self.layer_count(axis))
+            .max()
+            .unwrap_or(0);
+
+        Scramble::random_turns(self, 20 * layers.saturating_sub(2).max(1) as usize, seed)
+    }
+}
+
+/// A rotation of adjacent layers around one of the axes of a puzzle.
+///
+/// Positive amounts turn clockwise when looking at layer `0` of the axis.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct PuzzleTurn {
+    pub axis: usize,
+    pub layers: RangeInclusive<u32>,
+    pub amount: i32,
+}
+
+impl PuzzleTurn {
+    pub fn inverse(&self) -> Self {
+        Self {
+            amount: -self.amount,
+            ..self.clone()
+        }
+    }
+}
+
+/// Merges consecutive turns of the same layers and drops the ones cancelling out, like
+/// [`merge_moves`](crate::notation::merge_moves) does for moves.
+pub fn merge_turns(turns: &[PuzzleTurn], steps_per_revolution: i32) -> Vec<PuzzleTurn> {
+    let mut merged: Vec<PuzzleTurn> = Vec::with_capacity(turns.len());
+
+    for turn in turns {
+        match merged.last_mut() {
+            Some(last) if last.axis == turn.axis && last.layers == turn.layers => {
+                match (last.amount + turn.amount).rem_euclid(steps_per_revolution) {
+                    0 => {
+                        merged.pop();
+                    }
+                    amount if amount > steps_per_revolution / 2 => {
+                        last.amount = amount - steps_per_revolution
+                    }
+                    amount => last.amount = amount,
+                }
+            }
+            _ => merged.push(turn.clone()),
+        }
+    }
+
+    merged
+}
+
+impl From<Turn> for PuzzleTurn {
+    fn from(turn: Turn) -> Self {
+        Self {
+            axis: turn.axis.index(),
+            layers: turn.layers,
+            amount: turn.quarter_turns,
+        }
+    }
+}
+
+impl From<PuzzleTurn> for Turn {
+    fn from(turn: PuzzleTurn) -> Self {
+        Self {
+            axis: Axis::ALL[turn.axis],
+            layers: turn.layers,
+            quarter_turns: turn.amount,
+        }
+    }
+}
+
+impl From<PyraminxTurn> for PuzzleTurn {
+    fn from(turn: PyraminxTurn) -> Self {
+        Self {
+            axis: turn.vertex.index(),
+            layers: turn.layers,
+            amount: turn.thirds,
+        }
+    }
+}
+
+impl From<PuzzleTurn> for PyraminxTurn {
+    fn from(turn: PuzzleTurn) -> Self {
+        Self {
+            vertex: Vertex::ALL[turn.axis],
+            layers: turn.layers,
+            thirds: turn.amount,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum PuzzleKind {
+    Rubik,
+    Pyraminx,
+    Mirror,
+}
+
+impl PuzzleKind {
+    pub fn entry(self) -> &'static PuzzleEntry {
+        REGISTRY
+            .iter()
+            .find(|entry| entry.kind == self)
+            .expect("every puzzle is registered")
+    }
+}
+
+/// What is needed to offer a puzzle for selection.
+#[derive(Debug, Clone)]
+pub struct PuzzleEntry {
+    pub kind: PuzzleKind,
+    pub name: &'static str,
+    /// Dimensions the puzzle can be created with, a single one if its size is fixed.
+    pub dimensions: RangeInclusive<u32>,
+    pub default_dimension: u32,
+    /// Creates the solved state, which is all the command line needs.
+    pub create: fn(u32) -> Box<dyn Puzzle>,
+    /// Creates the solved puzzle together with everything needed to draw it.
+    #[cfg(feature = "render")]
+    pub create_render: fn(u32) -> Box<dyn PuzzleRender>,
+}
+
+/// All puzzles which can be selected at runtime.
+pub const REGISTRY: [PuzzleEntry; 3] = [
+    PuzzleEntry {
+        kind: PuzzleKind::Rubik,
+        name: "Rubik's Cube",
+        dimensions: 1..=100,
+        default_dimension: 3,
+        create: |dimension| Box::new(RubikState::new(dimension)),
+        #[cfg(feature = "render")]
+        create_render: |dimension| Box::new(rubiks::Rubik::new(dimension)),
+    },
+    PuzzleEntry {
+        kind: PuzzleKind::Pyraminx,
+        name: "Pyraminx",
+        dimensions: 1..=7,
+        default_dimension: 3,
+        create: |dimension| Box::new(PyraminxState::new(dimension)),
+        #[cfg(feature = "render")]
+        create_render: |dimension| Box::new(pyraminx::Pyraminx::new(dimension)),
+    },
+    PuzzleEntry {
+        kind: PuzzleKind::Mirror,
+        name: "Mirror Cube",
+        dimensions: 3..=3,
+        default_dimension: 3,
+        create: |_| Box::new(MirrorState::new()),
+        #[cfg(feature = "render")]
+        create_render: |_| Box::new(mirror::Mirror::new()),
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(axis: usize, layers: RangeInclusive<u32>, amount: i32) -> PuzzleTurn {
+        PuzzleTurn {
+            axis,
+            layers,
+            amount,
+        }
+    }
+
+    #[test]
+    fn registered_puzzles_parse_the_turns_they_format() {
+        for entry in &REGISTRY {
+            for dimension in entry.dimensions.clone().take(7) {
+                let mut puzzle = (entry.create)(dimension);
+                let scramble = puzzle.scramble(1).moves;
+
+                let steps = puzzle.steps_per_revolution();
+                let normalized = |turn: &PuzzleTurn| PuzzleTurn {
+                    amount: turn.amount.rem_euclid(steps),
+                    ..turn.clone()
+                };
+
+                for turn in puzzle.legal_turns().iter().chain(&scramble) {
+                    let notation = puzzle.format_turn(turn);
+                    let parsed = puzzle.parse_turns(&notation).unwrap();
+                    let parsed = parsed.iter().map(normalized).collect::<Vec<_>>();
+                    assert_eq!(parsed, [normalized(turn)], "{}", notation);
+                }
+
+                for turn in &scramble {
+                    puzzle.turn(turn);
+                }
+
+                assert_eq!(puzzle.is_solved(), scramble.is_empty(), "{}", entry.name);
+            }
+        }
+    }
+
+    #[test]
+    fn merges_turns_of_the_same_layers() {
+        assert!(merge_turns(&[turn(0, 0..=0, 1), turn(0, 0..=0, -1)], 4).is_empty());
+        assert_eq!(
+            merge_turns(&[turn(0, 0..=0, 1), turn(0, 0..=0, 1)], 4),
+            [turn(0, 0..=0, 2)]
+        );
+        assert_eq!(
+            merge_turns(
+                &[turn(1, 0..=0, 1), turn(1, 1..=1, 1), turn(1, 0..=1, 1)],
+                4
+            ),
+            [turn(1, 0..=0, 1), turn(1, 1..=1, 1), turn(1, 0..=1, 1)]
+        );
+        assert_eq!(
+            merge_turns(&[turn(0, 2..=2, 1), turn(1, 2..=2, 1)], 4),
+            [turn(0, 2..=2, 1), turn(1, 2..=2, 1)]
+        );
+        assert_eq!(
+            merge_turns(
+                &[
+                    turn(0, 0..=0, 1),
+                    turn(1, 0..=0, 1),
+                    turn(1, 0..=0, -1),
+                    turn(0, 0..=0, 1)
+                ],
+                4
+            ),
+            [turn(0, 0..=0, 2)]
+        );
+    }
+
+    #[test]
+    fn merged_turns_wrap_around_a_revolution() {
+        assert!(merge_turns(&[turn(0, 0..=0, 2), turn(0, 0..=0, 2)], 4).is_empty());
+        assert_eq!(
+            merge_turns(&[turn(0, 0..=0, 2), turn(0, 0..=0, 1)], 4),
+            [turn(0, 0..=0, -1)]
+        );
+        assert_eq!(
+            merge_turns(&[turn(0, 0..=0, -1), turn(0, 0..=0, -1)], 4),
+            [turn(0, 0..=0, 2)]
+        );
+        assert_eq!(
+            merge_turns(&[turn(0, 0..=0, 1), turn(0, 0..=0, 1)], 3),
+            [turn(0, 0..=0, -1)]
+        );
+        assert!(merge_turns(
+            &[turn(0, 0..=0, 1), turn(0, 0..=0, 1), turn(0, 0..=0, 1)],
+            3
+        )
+        .is_empty());
+    }
 }
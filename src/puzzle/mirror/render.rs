@@ -0,0 +1,309 @@
+use crate::notation::{NotationError, Span};
+use crate::puzzle::mirror::state::{MirrorState, Thickness};
+use crate::puzzle::render::{GAP_SIZE, TOTAL_SIDE_LENGTH};
+use crate::puzzle::rubiks::state::Axis;
+use crate::puzzle::{ColorSchemes, Puzzle, PuzzleRender, PuzzleTurn};
+use crate::scramble::Scramble;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+const PHYSICAL_SIDE_LENGTH: f32 = 0.057;
+const SCALE_FACTOR: f32 = TOTAL_SIDE_LENGTH / PHYSICAL_SIDE_LENGTH;
+
+const GAP_SPACE: f32 = 2.0 * GAP_SIZE;
+
+// opposite sides add up to 0.038
+// all middle pieces are 0.019
+// total side length 0.019 + 0.038 = 0.057
+const RIGHT_THICKNESS: f32 = 0.025 * SCALE_FACTOR;
+const LEFT_THICKNESS: f32 = 0.013 * SCALE_FACTOR;
+const TOP_THICKNESS: f32 = 0.029 * SCALE_FACTOR;
+const BOTTOM_THICKNESS: f32 = 0.009 * SCALE_FACTOR;
+const FRONT_THICKNESS: f32 = 0.021 * SCALE_FACTOR;
+const BACK_THICKNESS: f32 = 0.017 * SCALE_FACTOR;
+const MIDDLE_THICKNESS: f32 = 0.019 * SCALE_FACTOR - GAP_SPACE; // make middle piece smaller to accommodate for gaps
+
+const X_THICKNESS: [f32; 3] = [RIGHT_THICKNESS, MIDDLE_THICKNESS, LEFT_THICKNESS];
+const Y_THICKNESS: [f32; 3] = [TOP_THICKNESS, MIDDLE_THICKNESS, BOTTOM_THICKNESS];
+const Z_THICKNESS: [f32; 3] = [FRONT_THICKNESS, MIDDLE_THICKNESS, BACK_THICKNESS];
+const THICKNESSES: [[f32; 3]; 3] = [X_THICKNESS, Y_THICKNESS, Z_THICKNESS];
+
+const NUMBER_OF_SIDES: u32 = 6;
+const NUMBER_OF_COLORS: u32 = 1;
+
+#[derive(Default)]
+pub struct Mirror {
+    pub colors: Colors,
+    pub state: MirrorState,
+}
+
+/// The mirror cube has no stickers, all of its sides share the same color.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Colors {
+    pub body: Color,
+}
+
+impl Default for Colors {
+    fn default() -> Self {
+        Self::GOLD
+    }
+}
+
+impl Colors {
+    pub const GOLD: Colors = Colors {
+        body: crate::color::GOLD,
+    };
+
+    pub const SILVER: Colors = Colors {
+        body: crate::color::SILVER,
+    };
+
+    pub const PRESETS: [(&'static str, Colors); 2] =
+        [("gold", Self::GOLD), ("silver", Self::SILVER)];
+}
+
+impl Puzzle for Mirror {
+    fn axis_count(&self) -> usize {
+        self.state.axis_count()
+    }
+
+    fn layer_count(&self, axis: usize) -> u32 {
+        self.state.layer_count(axis)
+    }
+
+    fn steps_per_revolution(&self) -> i32 {
+        self.state.steps_per_revolution()
+    }
+
+    fn legal_turns(&self) -> Vec<PuzzleTurn> {
+        self.state.legal_turns()
+    }
+
+    fn parse_algorithm(&self, notation: &str) -> Result<Vec<(PuzzleTurn, Span)>, NotationError> {
+        self.state.parse_algorithm(notation)
+    }
+
+    fn format_turn(&self, turn: &PuzzleTurn) -> String {
+        self.state.format_turn(turn)
+    }
+
+    fn turn(&mut self, turn: &PuzzleTurn) {
+        Puzzle::turn(&mut self.state, turn);
+    }
+
+    fn pieces_in_layers(&self, axis: usize, layers: RangeInclusive<u32>) -> Vec<usize> {
+        Puzzle::pieces_in_layers(&self.state, axis, layers)
+    }
+
+    fn piece_layer(&self, piece: usize, axis: usize) -> u32 {
+        Puzzle::piece_layer(&self.state, piece, axis)
+    }
+
+    fn is_solved(&self) -> bool {
+        self.state.is_solved()
+    }
+
+    fn scramble(&self, seed: u64) -> Scramble<PuzzleTurn> {
+        self.state.scramble(seed)
+    }
+}
+
+impl PuzzleRender for Mirror {
+    fn create_texture(&self) -> Image {
+        let data = self
+            .colors
+            .body
+            .as_rgba_f32()
+            .into_iter()
+            .map(|color| (255.0 * color) as u8)
+            .collect();
+
+        Image::new(
+            Extent3d {
+                width: NUMBER_OF_COLORS,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+        )
+    }
+
+    fn create_material(&self, texture: Handle<Image>) -> StandardMaterial {
+        StandardMaterial {
+            base_color: Color::WHITE,
+            base_color_texture: Some(texture),
+            perceptual_roughness: 0.05,
+            metallic: 0.75,
+            ..default()
+        }
+    }
+
+    fn create_meshes(&self) -> Vec<Mesh> {
+        self.state
+            .cubies()
+            .iter()
+            .map(|cubie| {
+                // the mesh is built in the orientation of the solved state and rotated from there
+                let side_lengths = Vec3::from_array(Axis::ALL.map(|axis| {
+                    let layer =
+                        (MirrorState::DIMENSION as i32 - 1 - cubie.home()[axis.index()]) / 2;
+                    THICKNESSES[axis.index()][layer as usize]
+                }));
+
+                self.create_mesh(side_lengths)
+            })
+            .collect()
+    }
+
+    /// Every piece has a shape of its own, so nothing is shared. The meshes are in the order of
+    /// the cubies, which don't change their index when turning.
+    fn piece_meshes(&self) -> Vec<usize> {
+        (0..self.state.cubies().len()).collect()
+    }
+
+    fn set_color_schemes(&mut self, color_schemes: &ColorSchemes) {
+        self.colors = color_schemes.mirror;
+    }
+
+    fn axes(&self) -> Vec<Vec3> {
+        vec![Vec3::X, Vec3::Y, Vec3::Z]
+    }
+
+    fn pivot(&self) -> Vec3 {
+        Self::get_middle_translation()
+    }
+
+    /// Places the piece according to its current thicknesses, as the outer layers grow outwards
+    /// from the same middle layers no matter which pieces they consist of.
+    fn get_piece_transform(&self, piece: usize) -> Transform {
+        const HALF_MIDDLE_THICKNESS: f32 = MIDDLE_THICKNESS / 2.0;
+
+        let mut translation = Self::get_middle_translation();
+
+        for (axis, thickness) in Axis::ALL.into_iter().zip(self.state.thickness(piece)) {
+            let offset = HALF_MIDDLE_THICKNESS + GAP_SIZE + Self::get_thickness(thickness) / 2.0;
+
+            match self.state.cubie_layer(piece, axis) {
+                0 => translation[axis.index()] += offset,
+                1 => {}
+                _ => translation[axis.index()] -= offset,
+            }
+        }
+
+        let matrix = self.state.cubies()[piece].rotation().matrix();
+        let column = |j: usize| Vec3::from_array(matrix.map(|row| row[j] as f32));
+        let rotation = Quat::from_mat3(&Mat3::from_cols(column(0), column(1), column(2)));
+
+        Transform::from_translation(translation).with_rotation(rotation)
+    }
+
+    /// All outer sides, as the mirror cube has no stickers.
+    fn sticker_normals(&self, piece: usize) -> Vec<Vec3> {
+        let mut normals = Vec::new();
+
+        for (axis, direction) in Axis::ALL.into_iter().zip([Vec3::X, Vec3::Y, Vec3::Z]) {
+            match self.state.cubie_layer(piece, axis) {
+                0 => normals.push(direction),
+                1 => {}
+                _ => normals.push(-direction),
+            }
+        }
+
+        normals
+    }
+}
+
+impl Mirror {
+    pub fn new() -> Self {
+        Self {
+            colors: Colors::default(),
+            state: MirrorState::new(),
+        }
+    }
+
+    /// Position of the middle pieces, which the layers turn around. It is not the middle of the
+    /// cube, because the outer layers on opposite sides are not equally thick.
+    pub fn get_middle_translation() -> Vec3 {
+        Vec3::splat(0.5)
+            - (Vec3::new(RIGHT_THICKNESS, TOP_THICKNESS, FRONT_THICKNESS)
+                + MIDDLE_THICKNESS / 2.0
+                + GAP_SIZE)
+    }
+
+    fn get_thickness(thickness: Thickness) -> f32 {
+        THICKNESSES[thickness.axis.index()][thickness.layer as usize]
+    }
+
+    pub fn create_mesh(&self, side_lengths: Vec3) -> Mesh {
+        const NUMBER_OF_VERTICES_PER_SIDE: usize = 4;
+        const CAPACITY: usize = NUMBER_OF_VERTICES_PER_SIDE * NUMBER_OF_SIDES as usize;
+
+        let half_side_lengths = side_lengths / 2.0;
+
+        let mut positions: Vec<[f32; 3]> = Vec::with_capacity(CAPACITY);
+
+        // right left
+        for x in [half_side_lengths.x, -half_side_lengths.x] {
+            for y in [half_side_lengths.y, -half_side_lengths.y] {
+                for z in [half_side_lengths.z, -half_side_lengths.z] {
+                    positions.push([x, y, z]);
+                }
+            }
+        }
+
+        // top bottom
+        for y in [half_side_lengths.y, -half_side_lengths.y] {
+            for z in [half_side_lengths.z, -half_side_lengths.z] {
+                for x in [half_side_lengths.x, -half_side_lengths.x] {
+                    positions.push([x, y, z]);
+                }
+            }
+        }
+
+        // front back
+        for z in [half_side_lengths.z, -half_side_lengths.z] {
+            for x in [half_side_lengths.x, -half_side_lengths.x] {
+                for y in [half_side_lengths.y, -half_side_lengths.y] {
+                    positions.push([x, y, z]);
+                }
+            }
+        }
+
+        let mut normals: Vec<[f32; 3]> = Vec::with_capacity(CAPACITY);
+
+        for axis in 0..3 {
+            for sign in [1.0, -1.0] {
+                let mut normal = [sign, 0.0, 0.0];
+                normal.rotate_right(axis);
+
+                for _ in 0..NUMBER_OF_VERTICES_PER_SIDE {
+                    normals.push(normal);
+                }
+            }
+        }
+
+        let uvs: Vec<[f32; 2]> = vec![[0.0, 0.0]; CAPACITY];
+
+        let indices: Vec<u32> = vec![
+            0, 2, 1, 1, 2, 3, // right
+            4, 5, 6, 6, 5, 7, // left
+            8, 10, 9, 9, 10, 11, // top
+            12, 13, 14, 14, 13, 15, // bottom
+            16, 18, 17, 17, 18, 19, // front
+            20, 21, 22, 22, 21, 23, // back
+        ];
+
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        mesh.set_indices(Some(Indices::U32(indices)));
+
+        mesh
+    }
+}
@@ -0,0 +1,177 @@
+use crate::puzzle::rubiks::state::{Axis, Cubie, RubikState, Turn};
+use std::ops::RangeInclusive;
+
+/// The layer of the solved mirror cube a side of a piece comes from, which determines how thick
+/// the piece is along that side.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Thickness {
+    pub axis: Axis,
+    pub layer: u32,
+}
+
+/// A 3x3x3 whose layers all have different thicknesses, so that it changes its shape when turned
+/// and is solved as soon as it is a cube again.
+///
+/// The pieces move exactly like the cubies of a [`RubikState`], only their thicknesses are
+/// tracked on top of it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MirrorState {
+    cubies: RubikState,
+}
+
+impl Default for MirrorState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MirrorState {
+    pub const DIMENSION: u32 = 3;
+
+    pub fn new() -> Self {
+        Self {
+            cubies: RubikState::new(Self::DIMENSION),
+        }
+    }
+
+    pub fn cubies(&self) -> &[Cubie] {
+        self.cubies.cubies()
+    }
+
+    pub fn turn(&mut self, turn: &Turn) {
+        self.cubies.turn(turn);
+    }
+
+    /// Indices into [`MirrorState::cubies`] of all cubies which are currently in the given
+    /// layers.
+    pub fn cubies_in_layers(&self, axis: Axis, layers: RangeInclusive<u32>) -> Vec<usize> {
+        self.cubies.cubies_in_layers(axis, layers)
+    }
+
+    /// Layer of the given axis the cubie with the given index currently lies in.
+    pub fn cubie_layer(&self, cubie: usize, axis: Axis) -> u32 {
+        self.cubies.cubie_layer(cubie, axis)
+    }
+
+    /// Thickness of the cubie with the given index along each axis in [`Axis::ALL`] order.
+    pub fn thickness(&self, cubie: usize) -> [Thickness; 3] {
+        let cubie = &self.cubies.cubies()[cubie];
+        let matrix = cubie.rotation().matrix();
+
+        Axis::ALL.map(|axis| {
+            // the axis of the solved cube which got rotated onto this one
+            let home_axis = Axis::ALL
+                .into_iter()
+                .find(|home_axis| matrix[axis.index()][home_axis.index()] != 0)
+                .unwrap();
+
+            Thickness {
+                axis: home_axis,
+                layer: ((Self::DIMENSION as i32 - 1 - cubie.home()[home_axis.index()]) / 2) as u32,
+            }
+        })
+    }
+
+    /// Whether every side is flat, which is when all pieces of an outer layer are equally thick
+    /// along the axis of the layer.
+    pub fn is_solved(&self) -> bool {
+        Axis::ALL.into_iter().all(|axis| {
+            [0, Self::DIMENSION - 1].into_iter().all(|layer| {
+                let mut thicknesses = self
+                    .cubies_in_layers(axis, layer..=layer)
+                    .into_iter()
+                    .map(|cubie| self.thickness(cubie)[axis.index()]);
+
+                let first = thicknesses.next();
+                thicknesses.all(|thickness| Some(thickness) == first)
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(axis: Axis, layers: RangeInclusive<u32>, quarter_turns: i32) -> Turn {
+        Turn {
+            axis,
+            layers,
+            quarter_turns,
+        }
+    }
+
+    fn thicknesses(state: &MirrorState) -> Vec<[Thickness; 3]> {
+        (0..state.cubies().len())
+            .map(|cubie| state.thickness(cubie))
+            .collect()
+    }
+
+    #[test]
+    fn solved_again_after_undoing_turns() {
+        let turns = [
+            turn(Axis::X, 0..=0, 1),
+            turn(Axis::Y, 2..=2, -1),
+            turn(Axis::Z, 1..=1, 2),
+        ];
+        let mut state = MirrorState::new();
+
+        for turn in &turns {
+            state.turn(turn);
+            assert!(!state.is_solved());
+        }
+
+        for turn in turns.iter().rev() {
+            state.turn(&Turn {
+                quarter_turns: -turn.quarter_turns,
+                ..turn.clone()
+            });
+        }
+
+        assert!(state.is_solved());
+        assert_eq!(thicknesses(&state), thicknesses(&MirrorState::new()));
+    }
+
+    #[test]
+    fn rotations_are_solved() {
+        for axis in Axis::ALL {
+            let mut state = MirrorState::new();
+            state.turn(&turn(axis, 0..=2, 1));
+
+            assert!(state.is_solved());
+            assert_ne!(thicknesses(&state), thicknesses(&MirrorState::new()));
+        }
+    }
+
+    #[test]
+    fn single_turns_are_not_solved() {
+        for axis in Axis::ALL {
+            for layer in 0..MirrorState::DIMENSION {
+                let mut state = MirrorState::new();
+                state.turn(&turn(axis, layer..=layer, 1));
+
+                assert!(!state.is_solved(), "{:?} {}", axis, layer);
+            }
+        }
+    }
+
+    #[test]
+    fn thicknesses_follow_the_pieces() {
+        let solved = MirrorState::new();
+        let mut state = MirrorState::new();
+        state.turn(&turn(Axis::X, 0..=0, 1));
+
+        let turned = solved.cubies_in_layers(Axis::X, 0..=0);
+
+        for cubie in 0..solved.cubies().len() {
+            let [x, y, z] = solved.thickness(cubie);
+
+            if turned.contains(&cubie) {
+                // a turn around x swaps the sides along y and z
+                assert_eq!(state.thickness(cubie), [x, z, y]);
+            } else {
+                assert_eq!(state.thickness(cubie), [x, y, z]);
+            }
+        }
+    }
+}
@@ -1,226 +1,383 @@
-use super::{GAP_SIZE, TOTAL_SIDE_LENGTH};
-use crate::puzzle::Puzzle;
-use bevy::prelude::*;
-use bevy::render::mesh::{Indices, PrimitiveTopology};
-use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use crate::notation::{NotationError, NotationErrorKind, Span};
+use crate::puzzle::pyraminx::state::{PyraminxState, PyraminxTurn, Vertex};
+use crate::puzzle::{Puzzle, PuzzleTurn};
+use crate::scramble::Scramble;
+use std::fmt::{Display, Formatter};
+use std::iter::Peekable;
+use std::ops::RangeInclusive;
+use std::str::CharIndices;
+use std::str::FromStr;
 
-const NUMBER_OF_TETRAHEDRON_SIDES: u32 = 4;
-const NUMBER_OF_BIPYRAMID_SIDES: u32 = 8;
-const NUMBER_OF_COLORS: u32 = NUMBER_OF_TETRAHEDRON_SIDES + 1;
+pub mod state;
 
-const NUMBER_OF_VERTICES_PER_SIDE: u32 = 3;
-const HALF_TOTAL_SIDE_LENGTH: f32 = TOTAL_SIDE_LENGTH / 2.0;
+#[cfg(feature = "render")]
+mod render;
 
-const ROOT_3: f32 = 1.7320508;
-const ROOT_6: f32 = 2.4494898;
+#[cfg(feature = "render")]
+pub use render::{Colors, Pyraminx};
 
-const FACE_HEIGHT: f32 = HALF_TOTAL_SIDE_LENGTH * ROOT_3;
-const THIRD_FACE_HEIGHT: f32 = FACE_HEIGHT / 3.0;
+impl Puzzle for PyraminxState {
+    fn axis_count(&self) -> usize {
+        Vertex::ALL.len()
+    }
 
-const HEIGHT: f32 = (TOTAL_SIDE_LENGTH / 3.0) * ROOT_6;
-const HALF_HEIGHT: f32 = HEIGHT / 2.0;
+    fn layer_count(&self, _axis: usize) -> u32 {
+        self.dimension()
+    }
 
-pub struct Pyraminx {
-    pub dimension: u32,
-}
+    fn steps_per_revolution(&self) -> i32 {
+        3
+    }
+
+    fn legal_turns(&self) -> Vec<PuzzleTurn> {
+        let mut turns = Vec::new();
+
+        // turning the face opposite of a vertex is the same as turning all other layers back
+        for vertex in Vertex::ALL {
+            for layer in 0..self.dimension().saturating_sub(1) {
+                for thirds in [1, -1] {
+                    turns.push(PuzzleTurn {
+                        axis: vertex.index(),
+                        layers: layer..=layer,
+                        amount: thirds,
+                    });
+                }
+            }
+        }
 
-impl Puzzle for Pyraminx {
-    fn create_texture(&self) -> Image {
-        let colors: [[f32; 4]; NUMBER_OF_COLORS as usize] = [
-            crate::color::GREEN.as_rgba_f32(),
-            crate::color::BLUE.as_rgba_f32(),
-            crate::color::YELLOW.as_rgba_f32(),
-            crate::color::RED.as_rgba_f32(),
-            crate::color::GRAY.as_rgba_f32(),
-        ];
-
-        let data = colors
+        turns
+    }
+
+    fn parse_algorithm(&self, notation: &str) -> Result<Vec<(PuzzleTurn, Span)>, NotationError> {
+        let moves = parse_spanned_moves(notation)?;
+
+        moves
             .into_iter()
-            .flatten()
-            .map(|color| (255.0 * color) as u8)
-            .collect();
-
-        Image::new(
-            Extent3d {
-                width: NUMBER_OF_COLORS,
-                height: 1,
-                depth_or_array_layers: 1,
+            .map(|(m, span)| match m.to_turn(self.dimension()) {
+                Some(turn) => Ok((PuzzleTurn::from(turn), span)),
+                None => Err(NotationError {
+                    kind: NotationErrorKind::LayerOutOfRange {
+                        dimension: self.dimension(),
+                    },
+                    span,
+                }),
+            })
+            .collect()
+    }
+
+    fn format_turn(&self, turn: &PuzzleTurn) -> String {
+        let turn = PyraminxTurn::from(turn.clone());
+        PyraminxMove::from_turn(&turn, self.dimension()).to_string()
+    }
+
+    fn turn(&mut self, turn: &PuzzleTurn) {
+        PyraminxState::turn(self, &PyraminxTurn::from(turn.clone()));
+    }
+
+    fn pieces_in_layers(&self, axis: usize, layers: RangeInclusive<u32>) -> Vec<usize> {
+        PyraminxState::pieces_in_layers(self, Vertex::ALL[axis], layers)
+    }
+
+    fn piece_layer(&self, piece: usize, axis: usize) -> u32 {
+        PyraminxState::piece_layer(self, piece, Vertex::ALL[axis])
+    }
+
+    fn is_solved(&self) -> bool {
+        PyraminxState::is_solved(self)
+    }
+
+    fn scramble(&self, seed: u64) -> Scramble<PuzzleTurn> {
+        let moves = Scramble::pyraminx(self.dimension(), seed).moves;
+
+        Scramble {
+            seed,
+            moves: moves
+                .iter()
+                .map(|m| {
+                    PuzzleTurn::from(
+                        m.to_turn(self.dimension())
+                            .expect("scrambles only turn existing layers"),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A turn around one of the vertices, like `U`, `u`, `2U` or `2-3U'`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct PyraminxMove {
+    pub vertex: Vertex,
+    pub layers: PyraminxLayers,
+    /// Clockwise third turns when looking at the vertex, negative for counterclockwise ones.
+    pub amount: i32,
+}
+
+/// Layers turned by a move, counted from the tip at the vertex, which is layer 1.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PyraminxLayers {
+    /// Only the tip, written in lowercase like `u`.
+    Tip,
+    /// All layers but the face opposite of the vertex, like `U`.
+    Vertex,
+    /// Only the n-th layer, like `2U`, which is how the inner layers of bigger pyraminxes turn.
+    Single(u32),
+    /// All layers from the first to the second one, like `2-3U`.
+    Range(u32, u32),
+}
+
+impl Display for PyraminxMove {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let letter = self.vertex.letter();
+
+        match self.layers {
+            PyraminxLayers::Tip => write!(f, "{}", letter.to_ascii_lowercase())?,
+            PyraminxLayers::Vertex => write!(f, "{}", letter)?,
+            PyraminxLayers::Single(layer) => write!(f, "{}{}", layer, letter)?,
+            PyraminxLayers::Range(first, last) => write!(f, "{}-{}{}", first, last, letter)?,
+        }
+
+        if self.amount < 0 {
+            write!(f, "'")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl PyraminxMove {
+    pub fn inverse(&self) -> Self {
+        Self {
+            amount: -self.amount,
+            ..*self
+        }
+    }
+
+    /// Converts the move into a turn of a pyraminx with the given dimension or returns `None`
+    /// if it refers to layers the pyraminx does not have.
+    pub fn to_turn(&self, dimension: u32) -> Option<PyraminxTurn> {
+        let (first, last) = match self.layers {
+            PyraminxLayers::Tip => (0, 0),
+            PyraminxLayers::Vertex => (0, dimension.saturating_sub(2)),
+            PyraminxLayers::Single(layer) => (layer.checked_sub(1)?, layer - 1),
+            PyraminxLayers::Range(first, last) => (first.checked_sub(1)?, last.checked_sub(1)?),
+        };
+
+        if first > last || last >= dimension {
+            return None;
+        }
+
+        Some(PyraminxTurn {
+            vertex: self.vertex,
+            layers: first..=last,
+            thirds: self.amount,
+        })
+    }
+
+    /// The move which turns the same layers as the turn of a pyraminx with the given dimension,
+    /// preferring tip and vertex moves over the layers counted from the tip.
+    pub fn from_turn(turn: &PyraminxTurn, dimension: u32) -> Self {
+        let (first, last) = (*turn.layers.start(), *turn.layers.end());
+
+        let layers = match (first, last) {
+            (0, 0) => PyraminxLayers::Tip,
+            (0, last) if last == dimension.saturating_sub(2) => PyraminxLayers::Vertex,
+            (first, last) if first == last => PyraminxLayers::Single(first + 1),
+            (first, last) => PyraminxLayers::Range(first + 1, last + 1),
+        };
+
+        Self {
+            vertex: turn.vertex,
+            layers,
+            amount: match turn.thirds.rem_euclid(3) {
+                2 => -1,
+                thirds => thirds,
             },
-            TextureDimension::D2,
-            data,
-            TextureFormat::Rgba8UnormSrgb,
-        )
-    }
-
-    fn create_material(&self, texture: Handle<Image>) -> StandardMaterial {
-        StandardMaterial {
-            base_color: Color::WHITE,
-            base_color_texture: Some(texture),
-            perceptual_roughness: 0.15,
-            ..Default::default()
         }
     }
+}
+
+impl FromStr for PyraminxMove {
+    type Err = NotationError;
 
-    fn create_meshes(&self) -> Vec<(Mesh, Transform)> {
-        vec![(self.create_bipyramid_mesh(), Transform::IDENTITY)]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match parse_moves(s)?.as_slice() {
+            [m] => Ok(*m),
+            _ => Err(NotationError {
+                kind: NotationErrorKind::ExpectedSingleMove,
+                span: 0..s.len(),
+            }),
+        }
     }
 }
 
-impl Pyraminx {
-    pub fn new(dimension: u32) -> Self {
-        Self { dimension }
-    }
-
-    fn create_tetrahedron_mesh(&self) -> Mesh {
-        let back = [0.0, -HALF_HEIGHT, -THIRD_FACE_HEIGHT * 2.0];
-        let left = [-HALF_TOTAL_SIDE_LENGTH, -HALF_HEIGHT, THIRD_FACE_HEIGHT];
-        let right = [HALF_TOTAL_SIDE_LENGTH, -HALF_HEIGHT, THIRD_FACE_HEIGHT];
-        let top = [0.0, HALF_HEIGHT, 0.0];
-
-        let positions: Vec<[f32; 3]> = vec![
-            back, right, left, // bottom side
-            top, left, right, // front side
-            top, right, back, // right side
-            top, back, left, // left side
-        ];
-
-        let normals: Vec<[f32; 3]> = {
-            let back = Vec3::from_array(back);
-            let left = Vec3::from_array(left);
-            let right = Vec3::from_array(right);
-            let top = Vec3::from_array(top);
-
-            let bottom_normal = [0.0, -1.0, 0.0];
-            let front_normal = (left - top).cross(right - top).normalize().to_array();
-            let right_normal = (right - top).cross(back - top).normalize().to_array();
-            let left_normal = (back - top).cross(left - top).normalize().to_array();
-
-            [bottom_normal, front_normal, right_normal, left_normal]
-                .into_iter()
-                .flat_map(|normal| [normal; NUMBER_OF_VERTICES_PER_SIDE as usize])
-                .collect()
+/// Parses moves separated by whitespace, like `U L' r b'` or `2U 2-3R'` on bigger pyraminxes.
+pub fn parse_moves(s: &str) -> Result<Vec<PyraminxMove>, NotationError> {
+    let moves = parse_spanned_moves(s)?;
+    Ok(moves.into_iter().map(|(m, _)| m).collect())
+}
+
+/// Like [`parse_moves`], but keeps the span of each move.
+pub fn parse_spanned_moves(s: &str) -> Result<Vec<(PyraminxMove, Span)>, NotationError> {
+    let mut moves = Vec::new();
+    let mut chars = s.char_indices().peekable();
+
+    let unexpected = |(i, c): (usize, char)| NotationError {
+        kind: NotationErrorKind::UnexpectedCharacter(c),
+        span: i..i + c.len_utf8(),
+    };
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let prefix = match parse_number(&mut chars)? {
+            Some(first) => {
+                let last = match chars.next_if(|(_, c)| *c == '-') {
+                    Some(_) => parse_number(&mut chars)?,
+                    None => Some(first),
+                };
+
+                Some((first, last))
+            }
+            None => None,
         };
 
-        let indices: Vec<u32> = (0..NUMBER_OF_TETRAHEDRON_SIDES)
-            .flat_map(|i| {
-                let offset = i * NUMBER_OF_VERTICES_PER_SIDE;
-                offset..offset + NUMBER_OF_VERTICES_PER_SIDE
-            })
-            .collect();
-
-        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
-        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
-        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
-        //mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
-        mesh.set_indices(Some(Indices::U32(indices)));
-
-        mesh
-    }
-
-    fn create_bipyramid_mesh(&self) -> Mesh {
-        const TOTAL_DEPTH: f32 = FACE_HEIGHT + THIRD_FACE_HEIGHT;
-        const HALF_TOTAL_DEPTH: f32 = TOTAL_DEPTH / 2.0;
-
-        let upper_back = [0.0, HALF_HEIGHT, -HALF_TOTAL_DEPTH];
-        let upper_left = [
-            -HALF_TOTAL_SIDE_LENGTH,
-            HALF_HEIGHT,
-            -HALF_TOTAL_DEPTH + FACE_HEIGHT,
-        ];
-        let upper_right = [
-            HALF_TOTAL_SIDE_LENGTH,
-            HALF_HEIGHT,
-            -HALF_TOTAL_DEPTH + FACE_HEIGHT,
-        ];
-
-        let lower_front = [0.0, -HALF_HEIGHT, HALF_TOTAL_DEPTH];
-        let lower_left = [
-            -HALF_TOTAL_SIDE_LENGTH,
-            -HALF_HEIGHT,
-            HALF_TOTAL_DEPTH - FACE_HEIGHT,
-        ];
-        let lower_right = [
-            HALF_TOTAL_SIDE_LENGTH,
-            -HALF_HEIGHT,
-            HALF_TOTAL_DEPTH - FACE_HEIGHT,
-        ];
-
-        #[rustfmt::skip]
-        let positions: Vec<[f32; 3]> = vec![
-            lower_front, lower_left, lower_right, // bottom
-            upper_back, upper_left, upper_right, // top
-            lower_front, upper_right, upper_left, // upper_front
-            lower_right, upper_back, upper_right, // upper_right
-            lower_left, upper_left, upper_back, // upper_left
-            upper_back, lower_right, lower_left, // lower_back
-            upper_right, lower_front, lower_right, // lower_right
-            upper_left, lower_left, lower_front, // lower_left
-        ];
-
-        let normals: Vec<[f32; 3]> = {
-            let upper_back = Vec3::from_array(upper_back);
-            let upper_left = Vec3::from_array(upper_left);
-            let upper_right = Vec3::from_array(upper_right);
-
-            let lower_front = Vec3::from_array(lower_front);
-            let lower_left = Vec3::from_array(lower_left);
-            let lower_right = Vec3::from_array(lower_right);
-
-            let top_normal = [0.0, 1.0, 0.0];
-            let bottom_normal = [0.0, -1.0, 0.0];
-            let upper_front_normal = (upper_right - lower_front)
-                .cross(upper_left - lower_front)
-                .normalize()
-                .to_array();
-            let upper_right_normal = (upper_back - lower_right)
-                .cross(upper_right - lower_right)
-                .normalize()
-                .to_array();
-            let upper_left_normal = (upper_left - lower_left)
-                .cross(upper_back - lower_left)
-                .normalize()
-                .to_array();
-            let lower_back_normal = (lower_right - upper_back)
-                .cross(lower_left - upper_back)
-                .normalize()
-                .to_array();
-            let lower_right_normal = (lower_front - upper_right)
-                .cross(lower_right - upper_right)
-                .normalize()
-                .to_array();
-            let lower_left_normal = (lower_left - upper_left)
-                .cross(lower_front - upper_left)
-                .normalize()
-                .to_array();
-
-            [
-                bottom_normal,
-                top_normal,
-                upper_front_normal,
-                upper_right_normal,
-                upper_left_normal,
-                lower_back_normal,
-                lower_right_normal,
-                lower_left_normal,
-            ]
-            .into_iter()
-            .flat_map(|normal| [normal; NUMBER_OF_VERTICES_PER_SIDE as usize])
-            .collect()
+        let Some((i, c)) = chars.next() else {
+            return Err(NotationError {
+                kind: NotationErrorKind::UnexpectedEnd,
+                span: start..s.len(),
+            });
         };
 
-        let indices: Vec<u32> = (0..NUMBER_OF_BIPYRAMID_SIDES)
-            .flat_map(|i| {
-                let offset = i * NUMBER_OF_VERTICES_PER_SIDE;
-                offset..offset + NUMBER_OF_VERTICES_PER_SIDE
-            })
-            .collect();
+        let Some(vertex) = Vertex::from_letter(c.to_ascii_uppercase()) else {
+            return Err(unexpected((i, c)));
+        };
+
+        let layers = match prefix {
+            None if c.is_ascii_lowercase() => PyraminxLayers::Tip,
+            None => PyraminxLayers::Vertex,
+            // the layers are counted from the tip, so there are no lowercase moves with them
+            Some((first, Some(last))) if c.is_ascii_uppercase() && first > 0 && first <= last => {
+                if first == last {
+                    PyraminxLayers::Single(first)
+                } else {
+                    PyraminxLayers::Range(first, last)
+                }
+            }
+            Some(_) => {
+                return Err(NotationError {
+                    kind: NotationErrorKind::InvalidLayers,
+                    span: start..i + c.len_utf8(),
+                })
+            }
+        };
+
+        let (amount, end) = match chars.next_if(|(_, c)| !c.is_whitespace()) {
+            None => (1, i + c.len_utf8()),
+            Some((i, prime @ ('\'' | '’' | '′'))) => (-1, i + prime.len_utf8()),
+            Some(next) => return Err(unexpected(next)),
+        };
+
+        if let Some(next) = chars.next_if(|(_, c)| !c.is_whitespace()) {
+            return Err(unexpected(next));
+        }
+
+        moves.push((
+            PyraminxMove {
+                vertex,
+                layers,
+                amount,
+            },
+            start..end,
+        ));
+    }
+
+    Ok(moves)
+}
+
+/// Takes the digits in front of the vertex letter, which are `None` if there are none.
+fn parse_number(chars: &mut Peekable<CharIndices>) -> Result<Option<u32>, NotationError> {
+    let mut digits = String::new();
+    let mut span = chars.peek().map_or(0, |(i, _)| *i)..0;
+
+    while let Some((i, digit)) = chars.next_if(|(_, c)| c.is_ascii_digit()) {
+        digits.push(digit);
+        span.end = i + 1;
+    }
+
+    if digits.is_empty() {
+        return Ok(None);
+    }
+
+    digits.parse().map(Some).map_err(|_| NotationError {
+        kind: NotationErrorKind::InvalidNumber,
+        span,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turns_of_every_layer_get_moves_which_parse_back() {
+        for dimension in 1..=7 {
+            for vertex in Vertex::ALL {
+                for first in 0..dimension {
+                    for last in first..dimension {
+                        for thirds in [1, -1] {
+                            let turn = PyraminxTurn {
+                                vertex,
+                                layers: first..=last,
+                                thirds,
+                            };
+
+                            let m = PyraminxMove::from_turn(&turn, dimension);
+                            let parsed = parse_moves(&m.to_string()).unwrap();
+                            assert_eq!(parsed, [m]);
+                            assert_eq!(m.to_turn(dimension), Some(turn));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn layers_are_counted_from_the_tip() {
+        let error = parse_moves("u U 2U 2-3R' 1-4l").unwrap_err();
+        assert_eq!(error.kind, NotationErrorKind::InvalidLayers);
+        assert_eq!(error.span, 13..17);
+
+        let moves = parse_moves("u U 2U 2-3R'").unwrap();
+        let layers = moves
+            .iter()
+            .map(|m| m.to_turn(5).unwrap().layers)
+            .collect::<Vec<_>>();
+        assert_eq!(layers, [0..=0, 0..=3, 1..=1, 1..=2]);
+
+        assert_eq!(moves[3].amount, -1);
+        assert_eq!(moves[3].to_turn(2), None);
+    }
+
+    #[test]
+    fn invalid_layers_are_rejected() {
+        for notation in ["0U", "3-2U", "2u", "2-U", "2"] {
+            assert!(parse_moves(notation).is_err(), "{}", notation);
+        }
+    }
 
-        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
-        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
-        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
-        //mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
-        mesh.set_indices(Some(Indices::U32(indices)));
+    #[test]
+    fn overflowing_layers_are_rejected() {
+        let error = parse_moves("U 99999999999R").unwrap_err();
+        assert_eq!(error.kind, NotationErrorKind::InvalidNumber);
+        assert_eq!(error.span, 2..13);
 
-        mesh
+        let error = parse_moves("2-99999999999R").unwrap_err();
+        assert_eq!(error.kind, NotationErrorKind::InvalidNumber);
+        assert_eq!(error.span, 2..13);
     }
 }
@@ -0,0 +1,354 @@
+use crate::notation::{NotationError, Span};
+use crate::puzzle::pyraminx::state::{Face, PyraminxPiece, PyraminxState, Shape, Vertex};
+use crate::puzzle::render::{share_meshes, GAP_SIZE, TOTAL_SIDE_LENGTH};
+use crate::puzzle::{ColorSchemes, Puzzle, PuzzleRender, PuzzleTurn};
+use crate::scramble::Scramble;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+const NUMBER_OF_TETRAHEDRON_SIDES: u32 = 4;
+const NUMBER_OF_COLORS: u32 = NUMBER_OF_TETRAHEDRON_SIDES + 1;
+
+const NUMBER_OF_VERTICES_PER_SIDE: u32 = 3;
+const HALF_TOTAL_SIDE_LENGTH: f32 = TOTAL_SIDE_LENGTH / 2.0;
+
+const ROOT_3: f32 = 1.7320508;
+const ROOT_6: f32 = 2.4494898;
+
+const FACE_HEIGHT: f32 = HALF_TOTAL_SIDE_LENGTH * ROOT_3;
+const THIRD_FACE_HEIGHT: f32 = FACE_HEIGHT / 3.0;
+
+const HEIGHT: f32 = (TOTAL_SIDE_LENGTH / 3.0) * ROOT_6;
+const QUARTER_HEIGHT: f32 = HEIGHT / 4.0;
+
+/// Corners of the whole pyraminx in the order of [`Vertex::ALL`], centered on its middle.
+const VERTICES: [Vec3; 4] = [
+    Vec3::new(0.0, 3.0 * QUARTER_HEIGHT, 0.0),
+    Vec3::new(-HALF_TOTAL_SIDE_LENGTH, -QUARTER_HEIGHT, THIRD_FACE_HEIGHT),
+    Vec3::new(HALF_TOTAL_SIDE_LENGTH, -QUARTER_HEIGHT, THIRD_FACE_HEIGHT),
+    Vec3::new(0.0, -QUARTER_HEIGHT, -2.0 * THIRD_FACE_HEIGHT),
+];
+
+type ColorMap = [u32; NUMBER_OF_TETRAHEDRON_SIDES as usize];
+
+pub struct Pyraminx {
+    pub dimension: u32,
+    pub colors: Colors,
+    pub state: PyraminxState,
+}
+
+/// Colors of the stickers on each side of the solved pyraminx.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Colors {
+    pub front: Color,
+    pub right: Color,
+    pub bottom: Color,
+    pub left: Color,
+}
+
+impl Default for Colors {
+    fn default() -> Self {
+        Self::CLASSIC
+    }
+}
+
+impl Colors {
+    pub const CLASSIC: Colors = Colors {
+        front: crate::color::GREEN,
+        right: crate::color::BLUE,
+        bottom: crate::color::YELLOW,
+        left: crate::color::RED,
+    };
+
+    pub const COLORBLIND: Colors = Colors {
+        front: crate::color::okabe_ito::BLUISH_GREEN,
+        right: crate::color::okabe_ito::BLUE,
+        bottom: crate::color::okabe_ito::YELLOW,
+        left: crate::color::okabe_ito::VERMILLION,
+    };
+
+    pub const HIGH_CONTRAST: Colors = Colors {
+        front: crate::color::high_contrast::GREEN,
+        right: crate::color::high_contrast::BLUE,
+        bottom: crate::color::high_contrast::YELLOW,
+        left: crate::color::high_contrast::RED,
+    };
+
+    pub const PRESETS: [(&'static str, Colors); 3] = [
+        ("classic", Self::CLASSIC),
+        ("colorblind", Self::COLORBLIND),
+        ("high contrast", Self::HIGH_CONTRAST),
+    ];
+}
+
+impl Puzzle for Pyraminx {
+    fn axis_count(&self) -> usize {
+        self.state.axis_count()
+    }
+
+    fn layer_count(&self, axis: usize) -> u32 {
+        self.state.layer_count(axis)
+    }
+
+    fn steps_per_revolution(&self) -> i32 {
+        self.state.steps_per_revolution()
+    }
+
+    fn legal_turns(&self) -> Vec<PuzzleTurn> {
+        self.state.legal_turns()
+    }
+
+    fn parse_algorithm(&self, notation: &str) -> Result<Vec<(PuzzleTurn, Span)>, NotationError> {
+        self.state.parse_algorithm(notation)
+    }
+
+    fn format_turn(&self, turn: &PuzzleTurn) -> String {
+        self.state.format_turn(turn)
+    }
+
+    fn turn(&mut self, turn: &PuzzleTurn) {
+        Puzzle::turn(&mut self.state, turn);
+    }
+
+    fn pieces_in_layers(&self, axis: usize, layers: RangeInclusive<u32>) -> Vec<usize> {
+        Puzzle::pieces_in_layers(&self.state, axis, layers)
+    }
+
+    fn piece_layer(&self, piece: usize, axis: usize) -> u32 {
+        Puzzle::piece_layer(&self.state, piece, axis)
+    }
+
+    fn is_solved(&self) -> bool {
+        self.state.is_solved()
+    }
+
+    fn scramble(&self, seed: u64) -> Scramble<PuzzleTurn> {
+        self.state.scramble(seed)
+    }
+}
+
+impl PuzzleRender for Pyraminx {
+    fn create_texture(&self) -> Image {
+        let colors: [[f32; 4]; NUMBER_OF_COLORS as usize] = [
+            self.colors.front.as_rgba_f32(),
+            self.colors.right.as_rgba_f32(),
+            self.colors.bottom.as_rgba_f32(),
+            self.colors.left.as_rgba_f32(),
+            crate::color::GRAY.as_rgba_f32(),
+        ];
+
+        let data = colors
+            .into_iter()
+            .flatten()
+            .map(|color| (255.0 * color) as u8)
+            .collect();
+
+        Image::new(
+            Extent3d {
+                width: NUMBER_OF_COLORS,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+        )
+    }
+
+    fn create_material(&self, texture: Handle<Image>) -> StandardMaterial {
+        StandardMaterial {
+            base_color: Color::WHITE,
+            base_color_texture: Some(texture),
+            perceptual_roughness: 0.15,
+            ..Default::default()
+        }
+    }
+
+    fn create_meshes(&self) -> Vec<Mesh> {
+        let (keys, _) = self.shared_meshes();
+
+        keys.into_iter()
+            .map(|(shape, color_map)| match shape {
+                Shape::Tetrahedron => self.create_tetrahedron_mesh(color_map),
+                Shape::Octahedron => self.create_octahedron_mesh(color_map),
+            })
+            .collect()
+    }
+
+    fn piece_meshes(&self) -> Vec<usize> {
+        let (_, indices) = self.shared_meshes();
+        indices
+    }
+
+    fn set_color_schemes(&mut self, color_schemes: &ColorSchemes) {
+        self.colors = color_schemes.pyraminx;
+    }
+
+    fn axes(&self) -> Vec<Vec3> {
+        VERTICES.iter().map(|vertex| vertex.normalize()).collect()
+    }
+
+    fn get_piece_transform(&self, piece: usize) -> Transform {
+        self.get_piece_transform_of(&self.state.pieces()[piece])
+    }
+
+    fn sticker_normals(&self, piece: usize) -> Vec<Vec3> {
+        let position = self.state.pieces()[piece].position();
+
+        self.state
+            .sticker_faces(position)
+            .map(|face| -VERTICES[face.opposite_vertex().index()].normalize())
+            .collect()
+    }
+}
+
+impl Pyraminx {
+    pub fn new(dimension: u32) -> Self {
+        Self {
+            dimension,
+            colors: Colors::default(),
+            state: PyraminxState::new(dimension),
+        }
+    }
+
+    /// Side length of the tetrahedra and octahedra, which are shrunk so that there is a gap of
+    /// about [`GAP_SIZE`] between neighbouring tetrahedra.
+    fn get_piece_side_length(&self) -> f32 {
+        TOTAL_SIDE_LENGTH / self.dimension as f32 - ROOT_6 * GAP_SIZE
+    }
+
+    fn get_piece_transform_of(&self, piece: &PyraminxPiece) -> Transform {
+        let scale = 4.0 * self.dimension as f32;
+        let translation = piece
+            .position()
+            .into_iter()
+            .zip(VERTICES)
+            .map(|(coordinate, vertex)| vertex * coordinate as f32 / scale)
+            .fold(Vec3::ZERO, std::ops::Add::add);
+
+        // the rotation which moves the vertices the same way the piece got moved
+        let rotation = piece.rotation();
+        let rotated = |i: usize| VERTICES[rotation.apply_to_vertex(Vertex::ALL[i]).index()];
+        let from = Mat3::from_cols(VERTICES[0], VERTICES[1], VERTICES[2]);
+        let to = Mat3::from_cols(rotated(0), rotated(1), rotated(2));
+        let rotation = Quat::from_mat3(&(to * from.inverse()));
+
+        Transform::from_translation(translation).with_rotation(rotation)
+    }
+
+    /// Pieces of the same shape share their mesh with all pieces which have stickers on the
+    /// same sides when solved.
+    fn shared_meshes(&self) -> (Vec<(Shape, ColorMap)>, Vec<usize>) {
+        share_meshes(
+            self.state
+                .pieces()
+                .iter()
+                .map(|piece| (piece.shape(), self.get_color_map(piece.home())))
+                .collect(),
+        )
+    }
+
+    fn get_color_map(&self, home: [i32; 4]) -> ColorMap {
+        let mut color_map: ColorMap = [NUMBER_OF_TETRAHEDRON_SIDES; 4];
+
+        for face in self.state.sticker_faces(home) {
+            color_map[face.index()] = face.index() as u32;
+        }
+
+        color_map
+    }
+
+    fn create_tetrahedron_mesh(&self, color_map: ColorMap) -> Mesh {
+        let scale = self.get_piece_side_length() / TOTAL_SIDE_LENGTH;
+
+        let sides = Face::ALL.map(|face| {
+            let vertex = face.opposite_vertex();
+            let corners = others(vertex).map(|other| VERTICES[other.index()] * scale);
+
+            (corners, -VERTICES[vertex.index()], color_map[face.index()])
+        });
+
+        create_mesh(&sides)
+    }
+
+    /// The octahedron fits between the tetrahedra, so its corners lie on the middle of the edges
+    /// of a tetrahedron twice their size.
+    fn create_octahedron_mesh(&self, color_map: ColorMap) -> Mesh {
+        let scale = self.get_piece_side_length() / TOTAL_SIDE_LENGTH;
+        let corner = |a: Vertex, b: Vertex| (VERTICES[a.index()] + VERTICES[b.index()]) * scale;
+
+        let sides = Face::ALL.into_iter().flat_map(|face| {
+            let vertex = face.opposite_vertex();
+            let [a, b, c] = others(vertex);
+            let normal = VERTICES[vertex.index()];
+
+            [
+                (
+                    [corner(a, b), corner(b, c), corner(c, a)],
+                    -normal,
+                    color_map[face.index()],
+                ),
+                (
+                    [corner(vertex, a), corner(vertex, b), corner(vertex, c)],
+                    normal,
+                    NUMBER_OF_TETRAHEDRON_SIDES,
+                ),
+            ]
+        });
+
+        create_mesh(&sides.collect::<Vec<_>>())
+    }
+}
+
+fn others(vertex: Vertex) -> [Vertex; 3] {
+    let mut others = [vertex; 3];
+
+    for (other, slot) in Vertex::ALL
+        .into_iter()
+        .filter(|other| *other != vertex)
+        .zip(others.iter_mut())
+    {
+        *slot = other;
+    }
+
+    others
+}
+
+/// Creates a mesh out of triangles, each given by its corners, the direction it faces and its
+/// color.
+fn create_mesh(sides: &[([Vec3; 3], Vec3, u32)]) -> Mesh {
+    let capacity = sides.len() * NUMBER_OF_VERTICES_PER_SIDE as usize;
+
+    let mut positions: Vec<[f32; 3]> = Vec::with_capacity(capacity);
+    let mut normals: Vec<[f32; 3]> = Vec::with_capacity(capacity);
+    let mut uvs: Vec<[f32; 2]> = Vec::with_capacity(capacity);
+
+    let color_size = 1.0 / NUMBER_OF_COLORS as f32;
+    let offset = color_size / 2.0;
+
+    for &([a, mut b, mut c], normal, color_id) in sides {
+        // counterclockwise when looking at the side, so that it doesn't get culled
+        if (b - a).cross(c - a).dot(normal) < 0.0 {
+            std::mem::swap(&mut b, &mut c);
+        }
+
+        for corner in [a, b, c] {
+            positions.push(corner.to_array());
+            normals.push(normal.normalize().to_array());
+            uvs.push([color_id as f32 * color_size + offset, offset]);
+        }
+    }
+
+    let indices: Vec<u32> = (0..positions.len() as u32).collect();
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.set_indices(Some(Indices::U32(indices)));
+
+    mesh
+}
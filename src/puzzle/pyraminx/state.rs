@@ -0,0 +1,308 @@
+use std::ops::RangeInclusive;
+
+/// The corners of the pyraminx when it sits on its bottom face with one edge facing the viewer.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Vertex {
+    Up,
+    Left,
+    Right,
+    Back,
+}
+
+impl Vertex {
+    pub const ALL: [Vertex; 4] = [Vertex::Up, Vertex::Left, Vertex::Right, Vertex::Back];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn letter(self) -> char {
+        match self {
+            Vertex::Up => 'U',
+            Vertex::Left => 'L',
+            Vertex::Right => 'R',
+            Vertex::Back => 'B',
+        }
+    }
+
+    pub fn from_letter(letter: char) -> Option<Vertex> {
+        match letter {
+            'U' => Some(Vertex::Up),
+            'L' => Some(Vertex::Left),
+            'R' => Some(Vertex::Right),
+            'B' => Some(Vertex::Back),
+            _ => None,
+        }
+    }
+
+    pub fn opposite_face(self) -> Face {
+        match self {
+            Vertex::Up => Face::Bottom,
+            Vertex::Left => Face::Right,
+            Vertex::Right => Face::Left,
+            Vertex::Back => Face::Front,
+        }
+    }
+}
+
+/// The sides of the pyraminx in the same order as the colors of the palette texture.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Face {
+    Front,
+    Right,
+    Bottom,
+    Left,
+}
+
+impl Face {
+    pub const ALL: [Face; 4] = [Face::Front, Face::Right, Face::Bottom, Face::Left];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn opposite_vertex(self) -> Vertex {
+        match self {
+            Face::Front => Vertex::Back,
+            Face::Right => Vertex::Left,
+            Face::Bottom => Vertex::Up,
+            Face::Left => Vertex::Right,
+        }
+    }
+}
+
+/// The other vertices in the order a clockwise third turn around the vertex with the same index
+/// moves them, when looking at that vertex.
+const CLOCKWISE_CYCLES: [[Vertex; 3]; 4] = [
+    [Vertex::Right, Vertex::Left, Vertex::Back],
+    [Vertex::Up, Vertex::Right, Vertex::Back],
+    [Vertex::Left, Vertex::Up, Vertex::Back],
+    [Vertex::Left, Vertex::Right, Vertex::Up],
+];
+
+/// A rotation of the pyraminx which maps vertices onto vertices, stored as the index of the
+/// vertex each vertex moves to.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Rotation([usize; 4]);
+
+impl Rotation {
+    pub const IDENTITY: Rotation = Rotation([0, 1, 2, 3]);
+
+    /// Clockwise third turns when looking at the vertex.
+    pub fn thirds(vertex: Vertex, thirds: i32) -> Self {
+        let mut third = Self::IDENTITY;
+
+        for (i, from) in CLOCKWISE_CYCLES[vertex.index()].iter().enumerate() {
+            third.0[from.index()] = CLOCKWISE_CYCLES[vertex.index()][(i + 1) % 3].index();
+        }
+
+        (0..thirds.rem_euclid(3)).fold(Self::IDENTITY, |rotation, _| rotation.then(third))
+    }
+
+    /// Moves the barycentric coordinates of a position along with the vertices.
+    pub fn apply(&self, position: [i32; 4]) -> [i32; 4] {
+        let mut result = [0; 4];
+
+        for (i, coordinate) in position.into_iter().enumerate() {
+            result[self.0[i]] = coordinate;
+        }
+
+        result
+    }
+
+    pub fn apply_to_vertex(&self, vertex: Vertex) -> Vertex {
+        Vertex::ALL[self.0[vertex.index()]]
+    }
+
+    pub fn apply_to_face(&self, face: Face) -> Face {
+        self.apply_to_vertex(face.opposite_vertex()).opposite_face()
+    }
+
+    /// Rotation that applies `self` first and `other` afterwards.
+    pub fn then(&self, other: Rotation) -> Rotation {
+        Rotation(self.0.map(|vertex| other.0[vertex]))
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Shape {
+    /// Tips, edges and the face centers of bigger pyraminxes.
+    Tetrahedron,
+    /// Centers, which sit between the tetrahedra with their tips towards the faces.
+    Octahedron,
+}
+
+/// A visible piece of the pyraminx.
+///
+/// Positions are the barycentric coordinates of the middle of the piece relative to the
+/// vertices in the order of [`Vertex::ALL`], scaled by four times the dimension so that they stay
+/// integers. They always add up to `4 * dimension` and a coordinate of `4 * dimension - 1` is the
+/// tip at that vertex.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct PyraminxPiece {
+    shape: Shape,
+    home: [i32; 4],
+    position: [i32; 4],
+    rotation: Rotation,
+}
+
+impl PyraminxPiece {
+    pub fn shape(&self) -> Shape {
+        self.shape
+    }
+
+    /// Position of the piece in the solved state.
+    pub fn home(&self) -> [i32; 4] {
+        self.home
+    }
+
+    pub fn position(&self) -> [i32; 4] {
+        self.position
+    }
+
+    /// Rotation of the piece relative to the solved state.
+    pub fn rotation(&self) -> Rotation {
+        self.rotation
+    }
+}
+
+/// A rotation of adjacent layers around one of the vertices.
+///
+/// Layer `0` is the tip at the vertex and layer `dimension - 1` the face opposite of it.
+/// Positive `thirds` turn clockwise when looking at the vertex.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PyraminxTurn {
+    pub vertex: Vertex,
+    pub layers: RangeInclusive<u32>,
+    pub thirds: i32,
+}
+
+impl PyraminxTurn {
+    pub fn inverse(&self) -> Self {
+        Self {
+            thirds: -self.thirds,
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PyraminxState {
+    dimension: u32,
+    pieces: Vec<PyraminxPiece>,
+}
+
+impl PyraminxState {
+    pub fn new(dimension: u32) -> Self {
+        assert!(dimension > 0, "a pyraminx needs at least one layer");
+
+        // the tetrahedra lie on the points of the grid adding up to `dimension - 1` and the
+        // octahedra in between them on the ones adding up to `dimension - 2`
+        let shapes = [
+            (Shape::Tetrahedron, dimension as i32 - 1, 1),
+            (Shape::Octahedron, dimension as i32 - 2, 2),
+        ];
+
+        let mut pieces = Vec::new();
+
+        for (shape, sum, offset) in shapes {
+            for a in 0..=sum {
+                for b in 0..=sum - a {
+                    for c in 0..=sum - a - b {
+                        let d = sum - a - b - c;
+                        let position = [a, b, c, d].map(|coordinate| 4 * coordinate + offset);
+
+                        if Self::sticker_faces_of(dimension, position).next().is_none() {
+                            continue;
+                        }
+
+                        pieces.push(PyraminxPiece {
+                            shape,
+                            home: position,
+                            position,
+                            rotation: Rotation::IDENTITY,
+                        });
+                    }
+                }
+            }
+        }
+
+        Self { dimension, pieces }
+    }
+
+    pub fn dimension(&self) -> u32 {
+        self.dimension
+    }
+
+    pub fn pieces(&self) -> &[PyraminxPiece] {
+        &self.pieces
+    }
+
+    pub fn turn(&mut self, turn: &PyraminxTurn) {
+        let rotation = Rotation::thirds(turn.vertex, turn.thirds);
+
+        for piece in self.pieces.iter_mut() {
+            let layer = Self::layer_of(self.dimension, piece.position[turn.vertex.index()]);
+
+            if turn.layers.contains(&layer) {
+                piece.position = rotation.apply(piece.position);
+                piece.rotation = piece.rotation.then(rotation);
+            }
+        }
+    }
+
+    /// Indices into [`PyraminxState::pieces`] of all pieces which are currently in the given
+    /// layers.
+    pub fn pieces_in_layers(&self, vertex: Vertex, layers: RangeInclusive<u32>) -> Vec<usize> {
+        self.pieces
+            .iter()
+            .enumerate()
+            .filter(|(_, piece)| {
+                layers.contains(&Self::layer_of(
+                    self.dimension,
+                    piece.position[vertex.index()],
+                ))
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Layer counted from the given vertex the piece with the given index currently lies in.
+    pub fn piece_layer(&self, piece: usize, vertex: Vertex) -> u32 {
+        Self::layer_of(self.dimension, self.pieces[piece].position[vertex.index()])
+    }
+
+    /// Whether every face shows a single color, which includes the tips.
+    pub fn is_solved(&self) -> bool {
+        let mut colors: [Option<Face>; 4] = [None; 4];
+
+        for piece in &self.pieces {
+            for home_face in self.sticker_faces(piece.home) {
+                let color = &mut colors[piece.rotation.apply_to_face(home_face).index()];
+
+                match color {
+                    None => *color = Some(home_face),
+                    Some(face) if *face == home_face => {}
+                    Some(_) => return false,
+                }
+            }
+        }
+
+        true
+    }
+
+    /// The faces of a piece at the given position which carry a sticker.
+    pub fn sticker_faces(&self, position: [i32; 4]) -> impl Iterator<Item = Face> {
+        Self::sticker_faces_of(self.dimension, position)
+    }
+
+    fn sticker_faces_of(dimension: u32, position: [i32; 4]) -> impl Iterator<Item = Face> {
+        Face::ALL.into_iter().filter(move |face| {
+            Self::layer_of(dimension, position[face.opposite_vertex().index()]) == dimension - 1
+        })
+    }
+
+    fn layer_of(dimension: u32, coordinate: i32) -> u32 {
+        ((4 * dimension as i32 - 1 - coordinate) / 4) as u32
+    }
+}
@@ -0,0 +1,178 @@
+use crate::puzzle::{mirror, pyraminx, rubiks};
+use crate::puzzle::{Puzzle, PuzzleKind};
+use bevy::prelude::{Handle, Image, Mesh, Resource, StandardMaterial, Transform, Vec3};
+use bevy::render::primitives::Aabb;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::ops::RangeInclusive;
+
+pub(super) const TOTAL_SIDE_LENGTH: f32 = 1.0;
+pub(super) const GAP_SIZE: f32 = 0.005;
+
+/// The sorted distinct keys, each describing a shared mesh, and the index of the key of every
+/// piece.
+pub(super) fn share_meshes<K: Ord + Clone>(pieces: Vec<K>) -> (Vec<K>, Vec<usize>) {
+    let mut keys = pieces.clone();
+    keys.sort();
+    keys.dedup();
+
+    let indices = pieces
+        .iter()
+        .map(|key| keys.binary_search(key).expect("every key is kept"))
+        .collect();
+
+    (keys, indices)
+}
+
+/// Everything the app needs to draw a [`Puzzle`], with the same piece indices.
+pub trait PuzzleRender: Puzzle {
+    fn create_texture(&self) -> Image;
+    fn create_material(&self, texture: Handle<Image>) -> StandardMaterial;
+
+    /// Meshes shared by the pieces, in the orientation of the solved state.
+    fn create_meshes(&self) -> Vec<Mesh>;
+
+    /// For every piece the index of its mesh in [`PuzzleRender::create_meshes`].
+    fn piece_meshes(&self) -> Vec<usize>;
+
+    /// Takes over the colors of this kind of puzzle for the next texture.
+    fn set_color_schemes(&mut self, color_schemes: &ColorSchemes);
+
+    /// Takes over the shape of this kind of puzzle for the next meshes.
+    fn set_styles(&mut self, _styles: &Styles) {}
+
+    /// Directions of the axes the layers turn around, each pointing towards its layer `0`.
+    fn axes(&self) -> Vec<Vec3>;
+
+    /// Angle in radians of a single step of [`PuzzleTurn::amount`](crate::puzzle::PuzzleTurn::amount).
+    fn turn_angle(&self) -> f32 {
+        std::f32::consts::TAU / self.steps_per_revolution() as f32
+    }
+
+    /// Point all axes go through.
+    fn pivot(&self) -> Vec3 {
+        Vec3::ZERO
+    }
+
+    /// Places the mesh of the piece after the turns applied with [`Puzzle::turn`].
+    fn get_piece_transform(&self, piece: usize) -> Transform;
+
+    /// Outward normals of the sides of the piece which can be grabbed to turn it.
+    fn sticker_normals(&self, piece: usize) -> Vec<Vec3>;
+
+    /// Access to the stickers for the editor, `None` if they can't be painted.
+    fn paint(&self) -> Option<&dyn Paint> {
+        None
+    }
+
+    fn paint_mut(&mut self) -> Option<&mut dyn Paint> {
+        None
+    }
+
+    /// Access to the chunks, `None` if every piece gets spawned on its own.
+    fn chunks(&self) -> Option<&dyn Chunks> {
+        None
+    }
+}
+
+/// Puzzles whose stickers can be painted in the editor. Colors are indices into the texture.
+pub trait Paint {
+    /// Number of colors a sticker can be painted with.
+    fn color_count(&self) -> usize;
+
+    /// Color of every sticker of the current state.
+    fn stickers(&self) -> Vec<usize>;
+
+    /// The sticker on the side of the piece with the given outward normal, which is one of
+    /// [`PuzzleRender::sticker_normals`].
+    fn sticker_index(&self, piece: usize, normal: Vec3) -> Option<usize>;
+
+    /// Mesh of the piece in its current place, but with its stickers in the given colors.
+    fn create_painted_mesh(&self, piece: usize, stickers: &[usize]) -> Mesh;
+
+    /// Changes the state to the one showing the given colors, unless it can't be turned into.
+    fn set_stickers(&mut self, stickers: &[usize]) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+/// Puzzles drawn as a few meshes, each showing a fixed part of the puzzle, instead of an entity
+/// for every piece.
+pub trait Chunks {
+    fn chunk_count(&self) -> usize;
+
+    /// Chunks showing parts of the given layers.
+    fn chunks_in_layers(&self, axis: usize, layers: RangeInclusive<u32>) -> Vec<usize>;
+
+    /// Meshes of the given chunks, without the `turning` layers.
+    fn create_chunk_meshes(
+        &self,
+        chunks: &[usize],
+        turning: Option<(usize, RangeInclusive<u32>)>,
+    ) -> Vec<Mesh>;
+
+    /// Mesh of the given layers while they turn.
+    fn create_layers_mesh(&self, axis: usize, layers: RangeInclusive<u32>) -> Mesh;
+
+    /// Box around the puzzle used for picking.
+    fn bounds(&self) -> Aabb;
+
+    /// The piece with the sticker at the given point on the side of [`Chunks::bounds`] with the
+    /// given outward normal.
+    fn piece_at(&self, point: Vec3, normal: Vec3) -> Option<usize>;
+}
+
+/// The colors assigned to each kind of puzzle.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Resource, Serialize, Deserialize)]
+pub struct ColorSchemes {
+    pub rubik: rubiks::Colors,
+    pub pyraminx: pyraminx::Colors,
+    pub mirror: mirror::Colors,
+}
+
+impl ColorSchemes {
+    /// Switches the given kind of puzzle to the preset after its current colors and returns the
+    /// name of that preset.
+    pub fn cycle(&mut self, kind: PuzzleKind) -> &'static str {
+        match kind {
+            PuzzleKind::Rubik => next_preset(&rubiks::Colors::PRESETS, &mut self.rubik),
+            PuzzleKind::Pyraminx => next_preset(&pyraminx::Colors::PRESETS, &mut self.pyraminx),
+            PuzzleKind::Mirror => next_preset(&mirror::Colors::PRESETS, &mut self.mirror),
+        }
+    }
+}
+
+/// The shapes assigned to each kind of puzzle, of which only the Rubik's Cube has a choice.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Resource, Serialize, Deserialize)]
+pub struct Styles {
+    pub rubik: rubiks::Style,
+}
+
+impl Styles {
+    /// Switches the given kind of puzzle to the preset after its current style and returns the
+    /// name of that preset, or `None` if the puzzle has no other styles.
+    pub fn cycle(&mut self, kind: PuzzleKind) -> Option<&'static str> {
+        match kind {
+            PuzzleKind::Rubik => Some(next_preset(&rubiks::Style::PRESETS, &mut self.rubik)),
+            PuzzleKind::Pyraminx | PuzzleKind::Mirror => None,
+        }
+    }
+}
+
+/// Switches to the preset after the current one, or to the first if the current one is none of
+/// them, and returns its name.
+fn next_preset<T: Copy + PartialEq>(
+    presets: &[(&'static str, T)],
+    current: &mut T,
+) -> &'static str {
+    let index = presets
+        .iter()
+        .position(|(_, preset)| preset == current)
+        .map_or(0, |index| (index + 1) % presets.len());
+
+    let (name, preset) = presets[index];
+    *current = preset;
+    name
+}
+
+/// The puzzle which is currently spawned into the view.
+#[derive(Resource)]
+pub struct CurrentPuzzle(pub Box<dyn PuzzleRender>);
@@ -0,0 +1,371 @@
+use crate::puzzle::rubiks::state::{Axis, Face, RubikState};
+
+/// Corners of a 3x3x3 in the order used by most solvers, named after their stickers.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Corner {
+    URF,
+    UFL,
+    ULB,
+    UBR,
+    DFR,
+    DLF,
+    DBL,
+    DRB,
+}
+
+impl Corner {
+    pub const ALL: [Corner; 8] = [
+        Corner::URF,
+        Corner::UFL,
+        Corner::ULB,
+        Corner::UBR,
+        Corner::DFR,
+        Corner::DLF,
+        Corner::DBL,
+        Corner::DRB,
+    ];
+
+    /// Faces of the corner's stickers, starting with the top or bottom one and continuing
+    /// clockwise.
+    pub fn faces(self) -> [Face; 3] {
+        use Face::*;
+
+        match self {
+            Corner::URF => [Top, Right, Front],
+            Corner::UFL => [Top, Front, Left],
+            Corner::ULB => [Top, Left, Back],
+            Corner::UBR => [Top, Back, Right],
+            Corner::DFR => [Bottom, Front, Right],
+            Corner::DLF => [Bottom, Left, Front],
+            Corner::DBL => [Bottom, Back, Left],
+            Corner::DRB => [Bottom, Right, Back],
+        }
+    }
+}
+
+/// Edges of a 3x3x3 in the order used by most solvers, named after their stickers.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Edge {
+    UR,
+    UF,
+    UL,
+    UB,
+    DR,
+    DF,
+    DL,
+    DB,
+    FR,
+    FL,
+    BL,
+    BR,
+}
+
+impl Edge {
+    pub const ALL: [Edge; 12] = [
+        Edge::UR,
+        Edge::UF,
+        Edge::UL,
+        Edge::UB,
+        Edge::DR,
+        Edge::DF,
+        Edge::DL,
+        Edge::DB,
+        Edge::FR,
+        Edge::FL,
+        Edge::BL,
+        Edge::BR,
+    ];
+
+    /// Faces of the edge's stickers, starting with the one that defines its orientation.
+    pub fn faces(self) -> [Face; 2] {
+        use Face::*;
+
+        match self {
+            Edge::UR => [Top, Right],
+            Edge::UF => [Top, Front],
+            Edge::UL => [Top, Left],
+            Edge::UB => [Top, Back],
+            Edge::DR => [Bottom, Right],
+            Edge::DF => [Bottom, Front],
+            Edge::DL => [Bottom, Left],
+            Edge::DB => [Bottom, Back],
+            Edge::FR => [Front, Right],
+            Edge::FL => [Front, Left],
+            Edge::BL => [Back, Left],
+            Edge::BR => [Back, Right],
+        }
+    }
+}
+
+/// A 3x3x3 described by which corner and edge sits in each slot and how it is twisted or
+/// flipped, which is far more compact than [`RubikState`](super::state::RubikState) and
+/// therefore what searches work with.
+///
+/// The centers are assumed to be in their home position.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct CubieCube {
+    /// Corner in each slot, indexed like [`Corner::ALL`].
+    pub corner_permutation: [u8; 8],
+    /// Clockwise twists of the corner in each slot.
+    pub corner_orientation: [u8; 8],
+    /// Edge in each slot, indexed like [`Edge::ALL`].
+    pub edge_permutation: [u8; 12],
+    /// Whether the edge in each slot is flipped.
+    pub edge_orientation: [u8; 12],
+}
+
+impl CubieCube {
+    pub const SOLVED: CubieCube = CubieCube {
+        corner_permutation: [0, 1, 2, 3, 4, 5, 6, 7],
+        corner_orientation: [0; 8],
+        edge_permutation: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+        edge_orientation: [0; 12],
+    };
+
+    /// Clockwise quarter turn of a single face.
+    pub fn face_turn(face: Face) -> CubieCube {
+        FACE_TURNS[face.index()]
+    }
+
+    /// The state after applying `other` to `self`.
+    pub fn then(&self, other: &CubieCube) -> CubieCube {
+        let mut result = *self;
+
+        for i in 0..8 {
+            let from = other.corner_permutation[i] as usize;
+            result.corner_permutation[i] = self.corner_permutation[from];
+            result.corner_orientation[i] =
+                (self.corner_orientation[from] + other.corner_orientation[i]) % 3;
+        }
+
+        for i in 0..12 {
+            let from = other.edge_permutation[i] as usize;
+            result.edge_permutation[i] = self.edge_permutation[from];
+            result.edge_orientation[i] =
+                (self.edge_orientation[from] + other.edge_orientation[i]) % 2;
+        }
+
+        result
+    }
+
+    /// Turns a face by the given amount of clockwise quarter turns.
+    pub fn turn(&self, face: Face, quarter_turns: i32) -> CubieCube {
+        let turn = Self::face_turn(face);
+
+        (0..quarter_turns.rem_euclid(4)).fold(*self, |cube, _| cube.then(&turn))
+    }
+
+    pub fn inverse(&self) -> CubieCube {
+        let mut result = *self;
+
+        for i in 0..8 {
+            let to = self.corner_permutation[i] as usize;
+            result.corner_permutation[to] = i as u8;
+            result.corner_orientation[to] = (3 - self.corner_orientation[i]) % 3;
+        }
+
+        for i in 0..12 {
+            let to = self.edge_permutation[i] as usize;
+            result.edge_permutation[to] = i as u8;
+            result.edge_orientation[to] = self.edge_orientation[i];
+        }
+
+        result
+    }
+
+    /// Reads the pieces off the stickers of a 3x3x3, relative to wherever its centers currently
+    /// are. Returns `None` for other dimensions.
+    pub fn from_state(state: &RubikState) -> Option<CubieCube> {
+        Self::read_pieces(state).filter(CubieCube::is_solvable)
+    }
+
+    /// Reads the pieces like [`CubieCube::from_state`], but keeps states which can't be reached
+    /// by turning, so that the reason can be found out. Returns `None` if the colors of a slot don't
+    /// match any piece.
+    pub fn read_pieces(state: &RubikState) -> Option<CubieCube> {
+        if state.dimension() != 3 {
+            return None;
+        }
+
+        let facelets = state.facelets();
+        let sticker = |face: Face, position: [i32; 3]| {
+            facelets[state.facelet_index(state.facelet_at(face, position))]
+        };
+
+        // the color of each center names the face it currently sits on
+        let mut faces_by_color = Face::ALL;
+
+        for face in Face::ALL {
+            faces_by_color[sticker(face, face.normal()).index()] = face;
+        }
+
+        let color =
+            |face: Face, position: [i32; 3]| faces_by_color[sticker(face, position).index()];
+
+        let mut cube = CubieCube::SOLVED;
+
+        for (slot, corner) in Corner::ALL.into_iter().enumerate() {
+            let faces = corner.faces();
+            let position = slot_position(&faces);
+            let colors = faces.map(|face| color(face, position));
+
+            let twist = colors.iter().position(|face| face.axis() == Axis::Y)?;
+            let colors = [0, 1, 2].map(|i| colors[(twist + i) % 3]);
+            let piece = Corner::ALL
+                .iter()
+                .position(|corner| corner.faces() == colors)?;
+
+            cube.corner_permutation[slot] = piece as u8;
+            cube.corner_orientation[slot] = twist as u8;
+        }
+
+        for (slot, edge) in Edge::ALL.into_iter().enumerate() {
+            let faces = edge.faces();
+            let position = slot_position(&faces);
+            let colors = faces.map(|face| color(face, position));
+            let flipped = [colors[1], colors[0]];
+
+            let (piece, flip) = Edge::ALL.iter().enumerate().find_map(|(piece, edge)| {
+                if edge.faces() == colors {
+                    Some((piece, 0))
+                } else if edge.faces() == flipped {
+                    Some((piece, 1))
+                } else {
+                    None
+                }
+            })?;
+
+            cube.edge_permutation[slot] = piece as u8;
+            cube.edge_orientation[slot] = flip;
+        }
+
+        Some(cube)
+    }
+
+    /// Whether the corners are an odd permutation, which has to match the edges on a real cube.
+    pub fn corner_parity(&self) -> bool {
+        permutation_parity(&self.corner_permutation)
+    }
+
+    pub fn edge_parity(&self) -> bool {
+        permutation_parity(&self.edge_permutation)
+    }
+
+    /// Whether the state can be reached by turning a real cube.
+    pub fn is_solvable(&self) -> bool {
+        let twist: u32 = self
+            .corner_orientation
+            .iter()
+            .map(|&twist| twist as u32)
+            .sum();
+        let flip: u32 = self.edge_orientation.iter().map(|&flip| flip as u32).sum();
+
+        is_permutation(&self.corner_permutation)
+            && is_permutation(&self.edge_permutation)
+            && self.corner_orientation.iter().all(|&twist| twist < 3)
+            && self.edge_orientation.iter().all(|&flip| flip < 2)
+            && twist.is_multiple_of(3)
+            && flip.is_multiple_of(2)
+            && self.corner_parity() == self.edge_parity()
+    }
+}
+
+impl Default for CubieCube {
+    fn default() -> Self {
+        Self::SOLVED
+    }
+}
+
+pub fn permutation_parity(permutation: &[u8]) -> bool {
+    let mut inversions = 0;
+
+    for i in 0..permutation.len() {
+        for j in i + 1..permutation.len() {
+            if permutation[i] > permutation[j] {
+                inversions += 1;
+            }
+        }
+    }
+
+    inversions % 2 == 1
+}
+
+/// Lehmer code of the permutation, which is `0` for the identity.
+pub fn permutation_index(permutation: &[u8]) -> usize {
+    (0..permutation.len()).fold(0, |index, i| {
+        let smaller_after = permutation[i + 1..]
+            .iter()
+            .filter(|&&value| value < permutation[i])
+            .count();
+
+        index * (permutation.len() - i) + smaller_after
+    })
+}
+
+/// Position of a 3x3x3 piece slot in [`RubikState`] coordinates.
+fn slot_position(faces: &[Face]) -> [i32; 3] {
+    let mut position = [0; 3];
+
+    for face in faces {
+        position[face.axis().index()] = face.normal()[face.axis().index()] * 2;
+    }
+
+    position
+}
+
+fn is_permutation(permutation: &[u8]) -> bool {
+    let mut seen = vec![false; permutation.len()];
+
+    permutation.iter().all(|&value| {
+        let value = value as usize;
+        value < seen.len() && !std::mem::replace(&mut seen[value], true)
+    })
+}
+
+/// Clockwise quarter turns ordered by [`Face::ALL`], each slot naming the piece that moves into
+/// it.
+const FACE_TURNS: [CubieCube; 6] = [
+    // right
+    CubieCube {
+        corner_permutation: [4, 1, 2, 0, 7, 5, 6, 3],
+        corner_orientation: [2, 0, 0, 1, 1, 0, 0, 2],
+        edge_permutation: [8, 1, 2, 3, 11, 5, 6, 7, 4, 9, 10, 0],
+        edge_orientation: [0; 12],
+    },
+    // left
+    CubieCube {
+        corner_permutation: [0, 2, 6, 3, 4, 1, 5, 7],
+        corner_orientation: [0, 1, 2, 0, 0, 2, 1, 0],
+        edge_permutation: [0, 1, 10, 3, 4, 5, 9, 7, 8, 2, 6, 11],
+        edge_orientation: [0; 12],
+    },
+    // top
+    CubieCube {
+        corner_permutation: [3, 0, 1, 2, 4, 5, 6, 7],
+        corner_orientation: [0; 8],
+        edge_permutation: [3, 0, 1, 2, 4, 5, 6, 7, 8, 9, 10, 11],
+        edge_orientation: [0; 12],
+    },
+    // bottom
+    CubieCube {
+        corner_permutation: [0, 1, 2, 3, 5, 6, 7, 4],
+        corner_orientation: [0; 8],
+        edge_permutation: [0, 1, 2, 3, 5, 6, 7, 4, 8, 9, 10, 11],
+        edge_orientation: [0; 12],
+    },
+    // front
+    CubieCube {
+        corner_permutation: [1, 5, 2, 3, 0, 4, 6, 7],
+        corner_orientation: [1, 2, 0, 0, 2, 1, 0, 0],
+        edge_permutation: [0, 9, 2, 3, 4, 8, 6, 7, 1, 5, 10, 11],
+        edge_orientation: [0, 1, 0, 0, 0, 1, 0, 0, 1, 1, 0, 0],
+    },
+    // back
+    CubieCube {
+        corner_permutation: [0, 1, 3, 7, 4, 5, 2, 6],
+        corner_orientation: [0, 0, 1, 2, 0, 0, 2, 1],
+        edge_permutation: [0, 1, 2, 11, 4, 5, 6, 10, 8, 9, 3, 7],
+        edge_orientation: [0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 1, 1],
+    },
+];
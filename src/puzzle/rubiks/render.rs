@@ -0,0 +1,453 @@
+use crate::notation::{NotationError, Span};
+use crate::puzzle::render::{share_meshes, GAP_SIZE, TOTAL_SIDE_LENGTH};
+use crate::puzzle::rubiks::state::{Cubie, Face, RubikState};
+use crate::puzzle::{Chunks, ColorSchemes, Paint, Puzzle, PuzzleRender, PuzzleTurn, Styles};
+use crate::scramble::Scramble;
+use bevy::prelude::{Color, Handle, Image, Mat3, Mesh, Quat, StandardMaterial, Transform, Vec3};
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::ops::RangeInclusive;
+
+mod chunks;
+mod mesh;
+
+const NUMBER_OF_SIDES: u32 = 6;
+const NUMBER_OF_COLORS: u32 = NUMBER_OF_SIDES + 1;
+
+/// Color of the plastic, which comes after the colors of the sides in the palette texture.
+const BODY_COLOR: u32 = NUMBER_OF_SIDES;
+
+/// Height of the stickers above the plastic relative to the side length of a cubie, which keeps
+/// them from flickering.
+const STICKER_HEIGHT: f32 = 0.002;
+
+pub struct Rubik {
+    pub dimension: u32,
+    pub colors: Colors,
+    pub style: Style,
+    pub state: RubikState,
+}
+
+/// Colors of the stickers on each side of the solved cube.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Colors {
+    pub right: Color,
+    pub left: Color,
+    pub top: Color,
+    pub bottom: Color,
+    pub front: Color,
+    pub back: Color,
+}
+
+impl Default for Colors {
+    fn default() -> Self {
+        Self::WESTERN
+    }
+}
+
+impl Colors {
+    /// White opposite of yellow and blue opposite of green.
+    pub const WESTERN: Colors = Colors {
+        right: crate::color::RED,
+        left: crate::color::ORANGE,
+        top: crate::color::YELLOW,
+        bottom: crate::color::WHITE,
+        front: crate::color::BLUE,
+        back: crate::color::GREEN,
+    };
+
+    /// White opposite of blue and yellow opposite of green.
+    pub const JAPANESE: Colors = Colors {
+        top: crate::color::BLUE,
+        front: crate::color::YELLOW,
+        ..Self::WESTERN
+    };
+
+    pub const COLORBLIND: Colors = Colors {
+        right: crate::color::okabe_ito::VERMILLION,
+        left: crate::color::okabe_ito::SKY_BLUE,
+        top: crate::color::okabe_ito::YELLOW,
+        bottom: crate::color::WHITE,
+        front: crate::color::okabe_ito::BLUE,
+        back: crate::color::okabe_ito::REDDISH_PURPLE,
+    };
+
+    pub const HIGH_CONTRAST: Colors = Colors {
+        right: crate::color::high_contrast::RED,
+        left: crate::color::high_contrast::ORANGE,
+        top: crate::color::high_contrast::YELLOW,
+        bottom: crate::color::WHITE,
+        front: crate::color::high_contrast::BLUE,
+        back: crate::color::high_contrast::GREEN,
+    };
+
+    pub const PRESETS: [(&'static str, Colors); 4] = [
+        ("western", Self::WESTERN),
+        ("japanese", Self::JAPANESE),
+        ("colorblind", Self::COLORBLIND),
+        ("high contrast", Self::HIGH_CONTRAST),
+    ];
+}
+
+/// Shape of the cubies, with all sizes except for the gap relative to the side length of a
+/// cubie, so that they look the same on every dimension.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Style {
+    /// Space between neighbouring cubies relative to the side length of the whole cube.
+    pub gap_size: f32,
+    /// Radius of the rounded edges and corners of the plastic.
+    pub bevel_radius: f32,
+    /// Space between the edges of a side and the edges of its sticker.
+    pub sticker_inset: f32,
+    /// Radius of the rounded corners of the stickers.
+    pub sticker_radius: f32,
+    /// Colors the plastic of the sides instead of putting stickers onto black plastic.
+    pub stickerless: bool,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self::ROUNDED
+    }
+}
+
+impl Style {
+    pub const ROUNDED: Style = Style {
+        gap_size: GAP_SIZE,
+        bevel_radius: 0.1,
+        sticker_inset: 0.06,
+        sticker_radius: 0.12,
+        stickerless: false,
+    };
+
+    pub const STICKERLESS: Style = Style {
+        stickerless: true,
+        ..Self::ROUNDED
+    };
+
+    /// Sharp cubies which are covered by their stickers, like the cube used to look.
+    pub const SHARP: Style = Style {
+        gap_size: GAP_SIZE,
+        bevel_radius: 0.0,
+        sticker_inset: 0.0,
+        sticker_radius: 0.0,
+        stickerless: false,
+    };
+
+    pub const PRESETS: [(&'static str, Style); 3] = [
+        ("rounded", Self::ROUNDED),
+        ("stickerless", Self::STICKERLESS),
+        ("sharp", Self::SHARP),
+    ];
+}
+
+type ColorMap = [u32; NUMBER_OF_SIDES as usize];
+
+impl Puzzle for Rubik {
+    fn axis_count(&self) -> usize {
+        self.state.axis_count()
+    }
+
+    fn layer_count(&self, axis: usize) -> u32 {
+        self.state.layer_count(axis)
+    }
+
+    fn steps_per_revolution(&self) -> i32 {
+        self.state.steps_per_revolution()
+    }
+
+    fn legal_turns(&self) -> Vec<PuzzleTurn> {
+        self.state.legal_turns()
+    }
+
+    fn parse_algorithm(&self, notation: &str) -> Result<Vec<(PuzzleTurn, Span)>, NotationError> {
+        self.state.parse_algorithm(notation)
+    }
+
+    fn format_turn(&self, turn: &PuzzleTurn) -> String {
+        self.state.format_turn(turn)
+    }
+
+    fn turn(&mut self, turn: &PuzzleTurn) {
+        Puzzle::turn(&mut self.state, turn);
+    }
+
+    fn pieces_in_layers(&self, axis: usize, layers: RangeInclusive<u32>) -> Vec<usize> {
+        Puzzle::pieces_in_layers(&self.state, axis, layers)
+    }
+
+    fn piece_layer(&self, piece: usize, axis: usize) -> u32 {
+        Puzzle::piece_layer(&self.state, piece, axis)
+    }
+
+    fn is_solved(&self) -> bool {
+        self.state.is_solved()
+    }
+
+    fn scramble(&self, seed: u64) -> Scramble<PuzzleTurn> {
+        self.state.scramble(seed)
+    }
+}
+
+impl PuzzleRender for Rubik {
+    fn create_texture(&self) -> Image {
+        let data = [
+            self.colors.right.as_rgba_f32(),
+            self.colors.left.as_rgba_f32(),
+            self.colors.top.as_rgba_f32(),
+            self.colors.bottom.as_rgba_f32(),
+            self.colors.front.as_rgba_f32(),
+            self.colors.back.as_rgba_f32(),
+            crate::color::BLACK.as_rgba_f32(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|color| (255.0 * color) as u8)
+        .collect();
+
+        Image::new(
+            Extent3d {
+                width: NUMBER_OF_COLORS,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+        )
+    }
+
+    fn create_material(&self, texture: Handle<Image>) -> StandardMaterial {
+        StandardMaterial {
+            base_color: Color::WHITE,
+            base_color_texture: Some(texture),
+            perceptual_roughness: 0.15,
+            ..Default::default()
+        }
+    }
+
+    fn create_meshes(&self) -> Vec<Mesh> {
+        let (color_maps, _) = self.shared_color_maps();
+
+        color_maps
+            .into_iter()
+            .map(|color_map| self.create_cube_mesh(color_map))
+            .collect()
+    }
+
+    fn piece_meshes(&self) -> Vec<usize> {
+        let (_, indices) = self.shared_color_maps();
+        indices
+    }
+
+    fn set_color_schemes(&mut self, color_schemes: &ColorSchemes) {
+        self.colors = color_schemes.rubik;
+    }
+
+    fn set_styles(&mut self, styles: &Styles) {
+        self.style = styles.rubik;
+    }
+
+    fn axes(&self) -> Vec<Vec3> {
+        vec![Vec3::X, Vec3::Y, Vec3::Z]
+    }
+
+    fn get_piece_transform(&self, piece: usize) -> Transform {
+        self.get_cubie_transform(&self.state.cubies()[piece])
+    }
+
+    fn sticker_normals(&self, piece: usize) -> Vec<Vec3> {
+        let position = self.state.cubies()[piece].position();
+
+        self.state
+            .sticker_faces(position)
+            .map(|face| Vec3::from_array(face.normal().map(|coordinate| coordinate as f32)))
+            .collect()
+    }
+
+    fn paint(&self) -> Option<&dyn Paint> {
+        Some(self)
+    }
+
+    fn paint_mut(&mut self) -> Option<&mut dyn Paint> {
+        Some(self)
+    }
+
+    fn chunks(&self) -> Option<&dyn Chunks> {
+        (self.dimension > chunks::MAX_SPAWNED_DIMENSION).then_some(self as &dyn Chunks)
+    }
+}
+
+/// Stickers are ordered like [`RubikState::facelets`] and colors like [`Face::ALL`].
+impl Paint for Rubik {
+    fn color_count(&self) -> usize {
+        NUMBER_OF_SIDES as usize
+    }
+
+    fn stickers(&self) -> Vec<usize> {
+        self.state
+            .facelets()
+            .into_iter()
+            .map(|color| color.index())
+            .collect()
+    }
+
+    fn sticker_index(&self, piece: usize, normal: Vec3) -> Option<usize> {
+        let position = self.state.cubies()[piece].position();
+        let normal = normal
+            .round()
+            .to_array()
+            .map(|coordinate| coordinate as i32);
+
+        self.state
+            .sticker_faces(position)
+            .find(|face| face.normal() == normal)
+            .map(|face| {
+                self.state
+                    .facelet_index(self.state.facelet_at(face, position))
+            })
+    }
+
+    fn create_painted_mesh(&self, piece: usize, stickers: &[usize]) -> Mesh {
+        let cubie = &self.state.cubies()[piece];
+        let inverse = cubie.rotation().inverse();
+        let mut color_map: ColorMap = [BODY_COLOR; NUMBER_OF_SIDES as usize];
+
+        // the mesh is turned with the cubie, so each sticker goes onto the side it came from
+        for face in self.state.sticker_faces(cubie.position()) {
+            let facelet = self.state.facelet_at(face, cubie.position());
+            let home_face = Face::from_normal(inverse.apply(face.normal()));
+            color_map[home_face.index()] = stickers[self.state.facelet_index(facelet)] as u32;
+        }
+
+        self.create_cube_mesh(color_map)
+    }
+
+    fn set_stickers(&mut self, stickers: &[usize]) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let facelets = stickers
+            .iter()
+            .map(|&color| Face::ALL.get(color).copied())
+            .collect::<Option<Vec<_>>>()
+            .ok_or("a sticker has a color the cube doesn't have")?;
+
+        self.state = RubikState::from_facelets(self.dimension, &facelets)?;
+        Ok(())
+    }
+}
+
+impl Rubik {
+    pub fn new(dimension: u32) -> Self {
+        Self {
+            dimension,
+            colors: Colors::default(),
+            style: Style::default(),
+            state: RubikState::new(dimension),
+        }
+    }
+
+    fn get_cube_side_length(&self) -> f32 {
+        let gap_space = (self.dimension - 1) as f32 * self.style.gap_size;
+        let remaining_space = TOTAL_SIDE_LENGTH - gap_space;
+        remaining_space / self.dimension as f32
+    }
+
+    /// Half the distance between the centers of neighbouring cubies, since positions are doubled.
+    fn get_half_step(&self) -> f32 {
+        (self.get_cube_side_length() + self.style.gap_size) / 2.0
+    }
+
+    fn get_cubie_transform(&self, cubie: &Cubie) -> Transform {
+        let [x, y, z] = cubie.position();
+        let translation = Vec3::new(x as f32, y as f32, z as f32) * self.get_half_step();
+
+        let matrix = cubie.rotation().matrix();
+        let column = |j: usize| Vec3::from_array(matrix.map(|row| row[j] as f32));
+        let rotation = Quat::from_mat3(&Mat3::from_cols(column(0), column(1), column(2)));
+
+        Transform::from_translation(translation).with_rotation(rotation)
+    }
+
+    /// Cubies share their mesh with all cubies which have stickers on the same sides when
+    /// solved, which leaves at most 26 meshes for any dimension.
+    fn shared_color_maps(&self) -> (Vec<ColorMap>, Vec<usize>) {
+        share_meshes(
+            self.state
+                .cubies()
+                .iter()
+                .map(|cubie| self.get_color_map(cubie.home()))
+                .collect(),
+        )
+    }
+
+    fn get_color_map(&self, home: [i32; 3]) -> ColorMap {
+        let mut color_map: ColorMap = [BODY_COLOR; NUMBER_OF_SIDES as usize];
+
+        for face in self.state.sticker_faces(home) {
+            color_map[face.index()] = face.index() as u32;
+        }
+
+        color_map
+    }
+
+    /// Half the side length of the stickers, which cover the whole side without stickers.
+    fn get_half_sticker_length(&self) -> f32 {
+        let half_cube_side_length = self.get_cube_side_length() / 2.0;
+
+        if self.style.stickerless {
+            return half_cube_side_length;
+        }
+
+        let inset = self.style.sticker_inset * self.get_cube_side_length();
+        (half_cube_side_length - inset).max(0.0)
+    }
+
+    /// Distance of the stickers from the center of their cubie.
+    fn get_sticker_distance(&self) -> f32 {
+        (0.5 + STICKER_HEIGHT) * self.get_cube_side_length()
+    }
+
+    /// The rounded plastic with a sticker on every side which has a color in the color map, or
+    /// with those sides colored if the cube is stickerless.
+    fn create_cube_mesh(&self, color_map: ColorMap) -> Mesh {
+        let cube_side_length = self.get_cube_side_length();
+        let mut builder = mesh::MeshBuilder::default();
+
+        let body_colors = if self.style.stickerless {
+            color_map
+        } else {
+            [BODY_COLOR; NUMBER_OF_SIDES as usize]
+        };
+
+        builder.add_rounded_cube(
+            cube_side_length / 2.0,
+            self.style.bevel_radius * cube_side_length,
+            body_colors,
+        );
+
+        if self.style.stickerless {
+            return builder.build();
+        }
+
+        for face in Face::ALL {
+            let color = color_map[face.index()];
+
+            if color == BODY_COLOR {
+                continue;
+            }
+
+            let normal = Vec3::from_array(face.normal().map(|value| value as f32));
+
+            builder.add_rounded_square(
+                normal * self.get_sticker_distance(),
+                face.axis().index(),
+                face.is_positive(),
+                self.get_half_sticker_length(),
+                self.style.sticker_radius * cube_side_length,
+                color,
+            );
+        }
+
+        builder.build()
+    }
+}
@@ -0,0 +1,238 @@
+use super::mesh::MeshBuilder;
+use super::{Rubik, BODY_COLOR};
+use crate::puzzle::render::TOTAL_SIDE_LENGTH;
+use crate::puzzle::rubiks::state::{Axis, Face, Facelet};
+use crate::puzzle::Chunks;
+use bevy::prelude::{Mesh, Vec3};
+use bevy::render::primitives::Aabb;
+use std::ops::RangeInclusive;
+
+/// Cubes up to this dimension spawn every cubie, bigger ones are drawn in chunks.
+pub(super) const MAX_SPAWNED_DIMENSION: u32 = 10;
+
+/// Stickers along each side of a chunk, which makes turns of single layers rebuild a small part
+/// of the big faces while keeping the number of entities low.
+const CHUNK_SIZE: u32 = 16;
+
+/// The gray body filling the gaps between the stickers, which is split where layers turn.
+const BODY_CHUNK: usize = 0;
+
+/// Chunks are the body followed by the square tiles of stickers of each face, row by row like
+/// the stickers of [`crate::puzzle::rubiks::state::RubikState::facelets`].
+///
+/// Only the sides of the cubies which carry a sticker get a quad, so hidden sides and the cubies
+/// in the middle don't cost anything.
+impl Chunks for Rubik {
+    fn chunk_count(&self) -> usize {
+        let chunks_per_side = self.chunks_per_side() as usize;
+        1 + Face::ALL.len() * chunks_per_side * chunks_per_side
+    }
+
+    fn chunks_in_layers(&self, axis: usize, layers: RangeInclusive<u32>) -> Vec<usize> {
+        let mut chunks = self
+            .facelets_in_layers(axis, &layers)
+            .map(|facelet| self.chunk_of(facelet))
+            .collect::<Vec<_>>();
+
+        chunks.push(BODY_CHUNK);
+        chunks.sort_unstable();
+        chunks.dedup();
+        chunks
+    }
+
+    fn create_chunk_meshes(
+        &self,
+        chunks: &[usize],
+        turning: Option<(usize, RangeInclusive<u32>)>,
+    ) -> Vec<Mesh> {
+        let colors = self.state.facelets();
+
+        chunks
+            .iter()
+            .map(|&chunk| {
+                let mut builder = MeshBuilder::default();
+
+                if chunk == BODY_CHUNK {
+                    self.add_body(&mut builder, turning.clone());
+                    return builder.build();
+                }
+
+                let facelets = self
+                    .facelets_of_chunk(chunk)
+                    .filter(|facelet| match &turning {
+                        Some((axis, layers)) => {
+                            !layers.contains(&self.facelet_layer(*facelet, *axis))
+                        }
+                        None => true,
+                    });
+
+                for facelet in facelets {
+                    let color = colors[self.state.facelet_index(facelet)];
+                    self.add_sticker(&mut builder, facelet, color.index() as u32);
+                }
+
+                builder.build()
+            })
+            .collect()
+    }
+
+    fn create_layers_mesh(&self, axis: usize, layers: RangeInclusive<u32>) -> Mesh {
+        let colors = self.state.facelets();
+        let mut builder = MeshBuilder::default();
+
+        for facelet in self.facelets_in_layers(axis, &layers) {
+            let color = colors[self.state.facelet_index(facelet)];
+            self.add_sticker(&mut builder, facelet, color.index() as u32);
+        }
+
+        let (min, max) = self.layer_bounds(&layers);
+        builder.add_box(self.body_box(axis, min, max), BODY_COLOR);
+
+        builder.build()
+    }
+
+    fn bounds(&self) -> Aabb {
+        let half_side_length = Vec3::splat(TOTAL_SIDE_LENGTH / 2.0);
+        Aabb::from_min_max(-half_side_length, half_side_length)
+    }
+
+    fn piece_at(&self, point: Vec3, normal: Vec3) -> Option<usize> {
+        let face = Face::from_normal(normal.round().to_array().map(|value| value as i32));
+        let max = self.dimension as i32 - 1;
+        let half_step = self.get_half_step();
+        let mut position = [0; 3];
+
+        for (axis, value) in position.iter_mut().enumerate() {
+            *value = if axis == face.axis().index() {
+                face.normal()[axis] * max
+            } else {
+                let index = ((point[axis] / half_step + max as f32) / 2.0).round() as i32;
+                2 * index.clamp(0, max) - max
+            };
+        }
+
+        self.state.cubie_at(position)
+    }
+}
+
+impl Rubik {
+    fn chunks_per_side(&self) -> u32 {
+        (self.dimension - 1) / CHUNK_SIZE + 1
+    }
+
+    fn chunk_of(&self, facelet: Facelet) -> usize {
+        let chunks_per_side = self.chunks_per_side() as usize;
+        let row = (facelet.row / CHUNK_SIZE) as usize;
+        let column = (facelet.column / CHUNK_SIZE) as usize;
+
+        1 + (facelet.face.index() * chunks_per_side + row) * chunks_per_side + column
+    }
+
+    fn facelets_of_chunk(&self, chunk: usize) -> impl Iterator<Item = Facelet> {
+        let chunks_per_side = self.chunks_per_side() as usize;
+        let tile = chunk - 1;
+        let face = Face::ALL[tile / (chunks_per_side * chunks_per_side)];
+        let first_row = (tile / chunks_per_side % chunks_per_side) as u32 * CHUNK_SIZE;
+        let first_column = (tile % chunks_per_side) as u32 * CHUNK_SIZE;
+        let rows = first_row..(first_row + CHUNK_SIZE).min(self.dimension);
+        let columns = first_column..(first_column + CHUNK_SIZE).min(self.dimension);
+
+        rows.flat_map(move |row| {
+            columns
+                .clone()
+                .map(move |column| Facelet { face, row, column })
+        })
+    }
+
+    fn facelets_in_layers<'a>(
+        &'a self,
+        axis: usize,
+        layers: &'a RangeInclusive<u32>,
+    ) -> impl Iterator<Item = Facelet> + 'a {
+        let n = self.dimension;
+
+        Face::ALL
+            .into_iter()
+            .flat_map(move |face| {
+                (0..n).flat_map(move |row| (0..n).map(move |column| Facelet { face, row, column }))
+            })
+            .filter(move |facelet| layers.contains(&self.facelet_layer(*facelet, axis)))
+    }
+
+    /// Layer of the given axis the cubie carrying the sticker lies in.
+    fn facelet_layer(&self, facelet: Facelet, axis: usize) -> u32 {
+        self.state
+            .layer_at(self.state.facelet_position(facelet), Axis::ALL[axis])
+    }
+
+    /// Stickers are plain squares here, as rounding their corners would multiply the vertices of
+    /// the many stickers of big cubes.
+    fn add_sticker(&self, builder: &mut MeshBuilder, facelet: Facelet, color: u32) {
+        let position = self.state.facelet_position(facelet);
+        let normal = Vec3::from_array(facelet.face.normal().map(|value| value as f32));
+        let center = Vec3::from_array(position.map(|value| value as f32)) * self.get_half_step()
+            + normal * self.get_sticker_distance();
+
+        builder.add_side(
+            center,
+            facelet.face.axis().index(),
+            facelet.face.is_positive(),
+            Vec3::splat(self.get_half_sticker_length()),
+            color,
+        );
+    }
+
+    /// The body without the turning layers, which leaves up to two boxes.
+    fn add_body(&self, builder: &mut MeshBuilder, turning: Option<(usize, RangeInclusive<u32>)>) {
+        let half_body_length = self.get_half_body_length();
+
+        let Some((axis, layers)) = turning else {
+            builder.add_box(
+                self.body_box(0, -half_body_length, half_body_length),
+                BODY_COLOR,
+            );
+            return;
+        };
+
+        let (min, max) = self.layer_bounds(&layers);
+
+        if min > -half_body_length {
+            builder.add_box(self.body_box(axis, -half_body_length, min), BODY_COLOR);
+        }
+
+        if max < half_body_length {
+            builder.add_box(self.body_box(axis, max, half_body_length), BODY_COLOR);
+        }
+    }
+
+    /// The body reaches up to the outer sides of the cubies, the stickers lie on top of it.
+    fn get_half_body_length(&self) -> f32 {
+        TOTAL_SIDE_LENGTH / 2.0
+    }
+
+    /// Range along their axis covered by the layers, cut off at the body.
+    fn layer_bounds(&self, layers: &RangeInclusive<u32>) -> (f32, f32) {
+        let max = self.dimension as i32 - 1;
+        let half_step = self.get_half_step();
+        let half_body_length = self.get_half_body_length();
+
+        // layer 0 lies on the positive side of every axis
+        let center = |layer: u32| (max - 2 * layer as i32) as f32 * half_step;
+
+        (
+            (center(*layers.end()) - half_step).max(-half_body_length),
+            (center(*layers.start()) + half_step).min(half_body_length),
+        )
+    }
+
+    /// The body cut to the given range of the given axis.
+    fn body_box(&self, axis: usize, min: f32, max: f32) -> (Vec3, Vec3) {
+        let half_body_length = self.get_half_body_length();
+        let mut from = Vec3::splat(-half_body_length);
+        let mut to = Vec3::splat(half_body_length);
+        from[axis] = min;
+        to[axis] = max;
+
+        (from, to)
+    }
+}
@@ -0,0 +1,186 @@
+use super::NUMBER_OF_COLORS;
+use bevy::prelude::{Mesh, Vec3};
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+
+/// Segments of the rounded edges of a body on either side of the edge.
+const BEVEL_SEGMENTS: u32 = 3;
+
+/// Segments of each rounded corner of a sticker.
+const CORNER_SEGMENTS: u32 = 4;
+
+/// Collects the triangles of a mesh, which all take their color from the palette texture.
+#[derive(Default)]
+pub(super) struct MeshBuilder {
+    positions: Vec<[f32; 3]>,
+    normals: Vec<[f32; 3]>,
+    uvs: Vec<[f32; 2]>,
+    indices: Vec<u32>,
+}
+
+impl MeshBuilder {
+    /// Adds a box without rounded edges, all in one color.
+    pub(super) fn add_box(&mut self, (min, max): (Vec3, Vec3), color: u32) {
+        for axis in 0..3 {
+            for positive in [true, false] {
+                let mut center = (min + max) / 2.0;
+                center[axis] = if positive { max[axis] } else { min[axis] };
+
+                self.add_side(center, axis, positive, (max - min) / 2.0, color);
+            }
+        }
+    }
+
+    /// Adds the rectangle around the center facing along the axis, spanning the given half size
+    /// along the other two axes.
+    pub(super) fn add_side(
+        &mut self,
+        center: Vec3,
+        axis: usize,
+        positive: bool,
+        half_size: Vec3,
+        color: u32,
+    ) {
+        let (normal, u, v) = side_directions(axis, positive);
+        let u = u * half_size[(axis + 1) % 3];
+        let v = v * half_size[(axis + 2) % 3];
+
+        let first = self.positions.len() as u32;
+
+        for corner in [-u - v, u - v, u + v, -u + v] {
+            self.add_vertex(center + corner, normal, color);
+        }
+
+        self.indices
+            .extend([0, 1, 2, 0, 2, 3].map(|index| first + index));
+    }
+
+    /// Adds a cube around the origin whose edges and corners are rounded with the given radius,
+    /// with the sides colored like [`Face::ALL`](crate::puzzle::rubiks::state::Face::ALL).
+    ///
+    /// Each side is a grid which is only subdivided where it bends, and every point of the grid
+    /// is pushed onto the rounded surface, so that neighbouring sides meet without seams.
+    pub(super) fn add_rounded_cube(&mut self, half_size: f32, radius: f32, colors: [u32; 6]) {
+        let radius = radius.clamp(0.0, half_size);
+        let inner = half_size - radius;
+
+        let mut coordinates = (0..=BEVEL_SEGMENTS)
+            .map(|segment| -half_size + radius * segment as f32 / BEVEL_SEGMENTS as f32)
+            .collect::<Vec<_>>();
+        coordinates.extend(coordinates.clone().into_iter().rev().map(|value| -value));
+        coordinates.dedup();
+
+        let count = coordinates.len() as u32;
+
+        for (index, color) in colors.into_iter().enumerate() {
+            let axis = index / 2;
+            let (normal, u, v) = side_directions(axis, index % 2 == 0);
+            let first = self.positions.len() as u32;
+
+            for &a in &coordinates {
+                for &b in &coordinates {
+                    let point = normal * half_size + u * a + v * b;
+                    let center = point.clamp(Vec3::splat(-inner), Vec3::splat(inner));
+                    let direction = (point - center).try_normalize().unwrap_or(normal);
+
+                    self.add_vertex(center + direction * radius, direction, color);
+                }
+            }
+
+            for i in 0..count - 1 {
+                for j in 0..count - 1 {
+                    let corner = |di: u32, dj: u32| first + (i + di) * count + j + dj;
+
+                    self.indices.extend([
+                        corner(0, 0),
+                        corner(1, 0),
+                        corner(1, 1),
+                        corner(0, 0),
+                        corner(1, 1),
+                        corner(0, 1),
+                    ]);
+                }
+            }
+        }
+    }
+
+    /// Adds a square around the center facing along the axis, whose corners are rounded with the
+    /// given radius.
+    pub(super) fn add_rounded_square(
+        &mut self,
+        center: Vec3,
+        axis: usize,
+        positive: bool,
+        half_size: f32,
+        radius: f32,
+        color: u32,
+    ) {
+        let radius = radius.clamp(0.0, half_size);
+        let inner = half_size - radius;
+        let (normal, u, v) = side_directions(axis, positive);
+
+        let middle = self.add_vertex(center, normal, color);
+
+        for quadrant in 0..4 {
+            let angle = quadrant as f32 * std::f32::consts::FRAC_PI_2;
+            let (sin, cos) = angle.sin_cos();
+            // goes through the corners (1, 1), (-1, 1), (-1, -1) and (1, -1) of the square
+            let corner = center + (u * (cos - sin) + v * (sin + cos)) * inner;
+
+            for segment in 0..=CORNER_SEGMENTS {
+                let angle =
+                    angle + std::f32::consts::FRAC_PI_2 * segment as f32 / CORNER_SEGMENTS as f32;
+                let (sin, cos) = angle.sin_cos();
+
+                self.add_vertex(corner + (u * cos + v * sin) * radius, normal, color);
+            }
+        }
+
+        let outline = 4 * (CORNER_SEGMENTS + 1);
+
+        for index in 0..outline {
+            self.indices.extend([
+                middle,
+                middle + 1 + index,
+                middle + 1 + (index + 1) % outline,
+            ]);
+        }
+    }
+
+    pub(super) fn build(self) -> Mesh {
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, self.positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, self.normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, self.uvs);
+        mesh.set_indices(Some(Indices::U32(self.indices)));
+
+        mesh
+    }
+
+    fn add_vertex(&mut self, position: Vec3, normal: Vec3, color: u32) -> u32 {
+        let color_size = 1.0 / NUMBER_OF_COLORS as f32;
+        let offset = color_size / 2.0;
+
+        self.positions.push(position.to_array());
+        self.normals.push(normal.to_array());
+        self.uvs.push([color as f32 * color_size + offset, offset]);
+
+        self.positions.len() as u32 - 1
+    }
+}
+
+/// Outward normal of the side facing along the axis and two directions within it, which go
+/// counterclockwise around the normal when looking at the side from outside.
+fn side_directions(axis: usize, positive: bool) -> (Vec3, Vec3, Vec3) {
+    let sign = if positive { 1.0 } else { -1.0 };
+
+    let mut normal = Vec3::ZERO;
+    let mut u = Vec3::ZERO;
+    let mut v = Vec3::ZERO;
+    normal[axis] = sign;
+    u[(axis + 1) % 3] = 1.0;
+    // flipped on negative sides, since u and v have to follow the right hand rule around the
+    // normal
+    v[(axis + 2) % 3] = sign;
+
+    (normal, u, v)
+}
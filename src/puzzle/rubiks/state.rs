@@ -0,0 +1,984 @@
+use crate::puzzle::rubiks::cubie::{Corner, CubieCube};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::ops::RangeInclusive;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    pub const ALL: [Axis; 3] = [Axis::X, Axis::Y, Axis::Z];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// The sides of the cube in the same order as the colors of the palette texture.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Face {
+    Right,
+    Left,
+    Top,
+    Bottom,
+    Front,
+    Back,
+}
+
+impl Face {
+    pub const ALL: [Face; 6] = [
+        Face::Right,
+        Face::Left,
+        Face::Top,
+        Face::Bottom,
+        Face::Front,
+        Face::Back,
+    ];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn axis(self) -> Axis {
+        match self {
+            Face::Right | Face::Left => Axis::X,
+            Face::Top | Face::Bottom => Axis::Y,
+            Face::Front | Face::Back => Axis::Z,
+        }
+    }
+
+    pub fn is_positive(self) -> bool {
+        matches!(self, Face::Right | Face::Top | Face::Front)
+    }
+
+    pub fn opposite(self) -> Face {
+        match self {
+            Face::Right => Face::Left,
+            Face::Left => Face::Right,
+            Face::Top => Face::Bottom,
+            Face::Bottom => Face::Top,
+            Face::Front => Face::Back,
+            Face::Back => Face::Front,
+        }
+    }
+
+    pub fn normal(self) -> [i32; 3] {
+        let sign = if self.is_positive() { 1 } else { -1 };
+        let mut normal = [0; 3];
+        normal[self.axis().index()] = sign;
+        normal
+    }
+
+    /// Letter of the face in move notation and facelet strings.
+    pub fn letter(self) -> char {
+        match self {
+            Face::Right => 'R',
+            Face::Left => 'L',
+            Face::Top => 'U',
+            Face::Bottom => 'D',
+            Face::Front => 'F',
+            Face::Back => 'B',
+        }
+    }
+
+    pub fn from_letter(letter: char) -> Option<Face> {
+        match letter {
+            'R' => Some(Face::Right),
+            'L' => Some(Face::Left),
+            'U' => Some(Face::Top),
+            'D' => Some(Face::Bottom),
+            'F' => Some(Face::Front),
+            'B' => Some(Face::Back),
+            _ => None,
+        }
+    }
+
+    pub fn from_normal(normal: [i32; 3]) -> Face {
+        match normal {
+            [1, 0, 0] => Face::Right,
+            [-1, 0, 0] => Face::Left,
+            [0, 1, 0] => Face::Top,
+            [0, -1, 0] => Face::Bottom,
+            [0, 0, 1] => Face::Front,
+            [0, 0, -1] => Face::Back,
+            _ => unreachable!("{:?} is not an axis aligned unit vector", normal),
+        }
+    }
+
+    /// Directions in which the columns and rows of the facelets grow when looking at this face,
+    /// following the layout of the usual cube net (top above front, bottom below it).
+    fn grid_directions(self) -> ([i32; 3], [i32; 3]) {
+        match self {
+            Face::Right => ([0, 0, -1], [0, -1, 0]),
+            Face::Left => ([0, 0, 1], [0, -1, 0]),
+            Face::Top => ([1, 0, 0], [0, 0, 1]),
+            Face::Bottom => ([1, 0, 0], [0, 0, -1]),
+            Face::Front => ([1, 0, 0], [0, -1, 0]),
+            Face::Back => ([-1, 0, 0], [0, -1, 0]),
+        }
+    }
+}
+
+impl Display for Face {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Face::Right => "right",
+            Face::Left => "left",
+            Face::Top => "top",
+            Face::Bottom => "bottom",
+            Face::Front => "front",
+            Face::Back => "back",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// Order of the faces in facelet strings, which is `URFDLB` like in Kociemba's solver.
+pub const FACELET_STRING_ORDER: [Face; 6] = [
+    Face::Top,
+    Face::Right,
+    Face::Front,
+    Face::Bottom,
+    Face::Left,
+    Face::Back,
+];
+
+/// A rotation of the cube which maps axes onto axes, stored as a signed permutation matrix.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Rotation([[i32; 3]; 3]);
+
+impl Rotation {
+    pub const IDENTITY: Rotation = Rotation([[1, 0, 0], [0, 1, 0], [0, 0, 1]]);
+
+    /// Clockwise quarter turns when looking at the positive side of the axis.
+    pub fn quarter_turns(axis: Axis, quarter_turns: i32) -> Self {
+        let quarter = match axis {
+            Axis::X => Rotation([[1, 0, 0], [0, 0, 1], [0, -1, 0]]),
+            Axis::Y => Rotation([[0, 0, -1], [0, 1, 0], [1, 0, 0]]),
+            Axis::Z => Rotation([[0, 1, 0], [-1, 0, 0], [0, 0, 1]]),
+        };
+
+        (0..quarter_turns.rem_euclid(4)).fold(Self::IDENTITY, |rotation, _| rotation.then(quarter))
+    }
+
+    /// All 24 rotations which keep the cube in place.
+    pub fn all() -> Vec<Rotation> {
+        let mut rotations = vec![Self::IDENTITY];
+        let mut next = 0;
+
+        while let Some(rotation) = rotations.get(next).copied() {
+            for axis in Axis::ALL {
+                let rotated = rotation.then(Self::quarter_turns(axis, 1));
+
+                if !rotations.contains(&rotated) {
+                    rotations.push(rotated);
+                }
+            }
+
+            next += 1;
+        }
+
+        rotations
+    }
+
+    pub fn inverse(&self) -> Rotation {
+        let mut result = [[0; 3]; 3];
+
+        for (i, row) in self.0.iter().enumerate() {
+            for (j, value) in row.iter().enumerate() {
+                result[j][i] = *value;
+            }
+        }
+
+        Rotation(result)
+    }
+
+    pub fn apply(&self, vector: [i32; 3]) -> [i32; 3] {
+        let mut result = [0; 3];
+
+        for (row, value) in self.0.iter().zip(result.iter_mut()) {
+            *value = row.iter().zip(vector).map(|(a, b)| a * b).sum();
+        }
+
+        result
+    }
+
+    /// Rotation that applies `self` first and `other` afterwards.
+    pub fn then(&self, other: Rotation) -> Rotation {
+        let mut result = [[0; 3]; 3];
+
+        for (i, row) in result.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..3).map(|k| other.0[i][k] * self.0[k][j]).sum();
+            }
+        }
+
+        Rotation(result)
+    }
+
+    pub fn matrix(&self) -> [[i32; 3]; 3] {
+        self.0
+    }
+}
+
+/// A rotation of adjacent layers around one of the axes of the cube.
+///
+/// Layer `0` is the layer on the positive side of the axis (right, top or front) and layer
+/// `dimension - 1` the one on the negative side. Positive `quarter_turns` turn clockwise when
+/// looking at the positive side of the axis.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Turn {
+    pub axis: Axis,
+    pub layers: RangeInclusive<u32>,
+    pub quarter_turns: i32,
+}
+
+impl Turn {
+    /// Turns the given layers, counted inwards starting with `0` at `face`, clockwise when looking
+    /// at `face`.
+    pub fn from_face(
+        face: Face,
+        layers: RangeInclusive<u32>,
+        quarter_turns: i32,
+        dimension: u32,
+    ) -> Self {
+        if face.is_positive() {
+            Self {
+                axis: face.axis(),
+                layers,
+                quarter_turns,
+            }
+        } else {
+            let last = dimension - 1;
+
+            Self {
+                axis: face.axis(),
+                layers: last - layers.end()..=last - layers.start(),
+                quarter_turns: -quarter_turns,
+            }
+        }
+    }
+
+    /// Rotates the whole cube.
+    pub fn rotation(axis: Axis, quarter_turns: i32, dimension: u32) -> Self {
+        Self {
+            axis,
+            layers: 0..=dimension - 1,
+            quarter_turns,
+        }
+    }
+
+    pub fn inverse(&self) -> Self {
+        Self {
+            quarter_turns: -self.quarter_turns,
+            ..self.clone()
+        }
+    }
+}
+
+/// A visible piece of the cube.
+///
+/// Positions are centered on the middle of the cube and doubled so that they stay integers,
+/// meaning each coordinate lies in `-(dimension - 1)..=(dimension - 1)` in steps of two.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Cubie {
+    home: [i32; 3],
+    position: [i32; 3],
+    rotation: Rotation,
+}
+
+impl Cubie {
+    /// Position of the cubie in the solved state.
+    pub fn home(&self) -> [i32; 3] {
+        self.home
+    }
+
+    pub fn position(&self) -> [i32; 3] {
+        self.position
+    }
+
+    /// Rotation of the cubie relative to the solved state.
+    pub fn rotation(&self) -> Rotation {
+        self.rotation
+    }
+}
+
+/// A sticker of the cube, identified by the face it currently lies on and its row and column
+/// within that face.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Facelet {
+    pub face: Face,
+    pub row: u32,
+    pub column: u32,
+}
+
+impl Display for Facelet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "row {} column {} of the {} side",
+            self.row + 1,
+            self.column + 1,
+            self.face
+        )
+    }
+}
+
+/// Why stickers don't describe a state the cube can be turned into.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum FaceletError {
+    /// Every side needs `dimension * dimension` stickers.
+    Length { expected: usize, found: usize },
+    /// Facelet strings need the same square number of stickers for every side.
+    StringLength(usize),
+    /// A facelet string contains a character which isn't a face letter.
+    UnknownLetter { letter: char, index: usize },
+    /// Each color has to show up as often as a side has stickers.
+    ColorCount {
+        color: Face,
+        count: usize,
+        expected: usize,
+    },
+    /// The colors of the stickers of a piece repeat, belong to opposite sides or are arranged
+    /// like the mirror image of a piece.
+    ImpossiblePiece(Vec<Facelet>),
+    /// The piece with these stickers shows up twice, so another one is missing.
+    DuplicatePiece(Vec<Facelet>),
+    /// The centers are arranged like the mirror image of a cube.
+    MirroredCenters,
+    /// A single corner is twisted in place.
+    TwistedCorner,
+    /// A single edge is flipped in place.
+    FlippedEdge,
+    /// Two pieces are swapped.
+    Parity,
+}
+
+impl Display for FaceletError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let list = |facelets: &[Facelet]| {
+            facelets
+                .iter()
+                .map(|facelet| facelet.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        match self {
+            FaceletError::Length { expected, found } => {
+                write!(f, "expected {} stickers but got {}", expected, found)
+            }
+            FaceletError::StringLength(length) => write!(
+                f,
+                "{} stickers can't be split into six square sides",
+                length
+            ),
+            FaceletError::UnknownLetter { letter, index } => write!(
+                f,
+                "'{}' at position {} is none of the face letters U, R, F, D, L and B",
+                letter,
+                index + 1
+            ),
+            FaceletError::ColorCount {
+                color,
+                count,
+                expected,
+            } => write!(
+                f,
+                "{} stickers have the color of the {} side instead of {}",
+                count, color, expected
+            ),
+            FaceletError::ImpossiblePiece(facelets) => write!(
+                f,
+                "no piece has the colors of the stickers at {}",
+                list(facelets)
+            ),
+            FaceletError::DuplicatePiece(facelets) => write!(
+                f,
+                "the piece with the stickers at {} shows up twice while another one is missing",
+                list(facelets)
+            ),
+            FaceletError::MirroredCenters => {
+                write!(
+                    f,
+                    "the centers are arranged like the mirror image of a cube"
+                )
+            }
+            FaceletError::TwistedCorner => write!(
+                f,
+                "a corner is twisted, the twists of all corners have to add up to whole turns"
+            ),
+            FaceletError::FlippedEdge => write!(
+                f,
+                "an edge is flipped, there has to be an even number of flipped edges"
+            ),
+            FaceletError::Parity => write!(
+                f,
+                "two pieces are swapped, which no turns can do without swapping two other pieces"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FaceletError {}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RubikState {
+    dimension: u32,
+    cubies: Vec<Cubie>,
+}
+
+impl RubikState {
+    pub fn new(dimension: u32) -> Self {
+        assert!(dimension > 0, "a cube needs at least one layer");
+
+        let max = dimension as i32 - 1;
+        let coordinates = || (-max..=max).step_by(2);
+
+        let mut cubies = Vec::new();
+
+        for x in coordinates() {
+            for y in coordinates() {
+                for z in coordinates() {
+                    let is_hidden = x.abs() != max && y.abs() != max && z.abs() != max;
+
+                    if is_hidden {
+                        continue;
+                    }
+
+                    cubies.push(Cubie {
+                        home: [x, y, z],
+                        position: [x, y, z],
+                        rotation: Rotation::IDENTITY,
+                    });
+                }
+            }
+        }
+
+        Self { dimension, cubies }
+    }
+
+    /// The state showing the given sticker colors, ordered like [`RubikState::facelets`], as
+    /// long as it can be reached by turning.
+    ///
+    /// Besides every piece showing up exactly once, the corners, the middle edges and the
+    /// middle centers have to fit together like on a 3x3x3. The remaining pieces of bigger
+    /// cubes can be arranged in any way.
+    pub fn from_facelets(dimension: u32, facelets: &[Face]) -> Result<Self, FaceletError> {
+        let mut state = Self::new(dimension);
+        let stickers_per_face = (dimension * dimension) as usize;
+        let expected = Face::ALL.len() * stickers_per_face;
+
+        if facelets.len() != expected {
+            return Err(FaceletError::Length {
+                expected,
+                found: facelets.len(),
+            });
+        }
+
+        for color in Face::ALL {
+            let count = facelets.iter().filter(|facelet| **facelet == color).count();
+
+            if count != stickers_per_face {
+                return Err(FaceletError::ColorCount {
+                    color,
+                    count,
+                    expected: stickers_per_face,
+                });
+            }
+        }
+
+        let rotations = Rotation::all();
+        let homes = state
+            .cubies
+            .iter()
+            .enumerate()
+            .map(|(index, cubie)| (cubie.home, index))
+            .collect::<HashMap<_, _>>();
+        let mut placed = vec![None; state.cubies.len()];
+
+        for position in state.cubies.iter().map(|cubie| cubie.position) {
+            let stickers = state
+                .sticker_faces(position)
+                .map(|face| state.facelet_at(face, position))
+                .map(|facelet| (facelet, facelets[state.facelet_index(facelet)]))
+                .collect::<Vec<_>>();
+            let locations = || stickers.iter().map(|(facelet, _)| *facelet).collect();
+
+            // rotations which turn the sides of the colors onto the sides the stickers are on
+            let mut candidates = rotations
+                .iter()
+                .filter(|rotation| {
+                    stickers.iter().all(|(facelet, color)| {
+                        rotation.apply(color.normal()) == facelet.face.normal()
+                    })
+                })
+                .peekable();
+
+            if candidates.peek().is_none() {
+                return Err(FaceletError::ImpossiblePiece(locations()));
+            }
+
+            // pieces with a single sticker fit in several rotations, each of them coming from
+            // another home of which any unused one will do
+            let (index, rotation) = candidates
+                .map(|rotation| (homes[&rotation.inverse().apply(position)], *rotation))
+                .find(|(index, _)| placed[*index].is_none())
+                .ok_or_else(|| FaceletError::DuplicatePiece(locations()))?;
+
+            placed[index] = Some((position, rotation));
+        }
+
+        for (cubie, (position, rotation)) in
+            state.cubies.iter_mut().zip(placed.into_iter().flatten())
+        {
+            cubie.position = position;
+            cubie.rotation = rotation;
+        }
+
+        if dimension % 2 == 1 && dimension > 1 {
+            state.check_middle_pieces()?;
+        } else if dimension > 1 {
+            state.check_corners()?;
+        }
+
+        Ok(state)
+    }
+
+    /// Checks the pieces of an odd cube which also make up a 3x3x3, relative to its centers.
+    fn check_middle_pieces(&self) -> Result<(), FaceletError> {
+        let max = self.dimension as i32 - 1;
+        let reduce = |vector: [i32; 3]| vector.map(|coordinate| coordinate.signum() * 2);
+
+        let reduced = RubikState {
+            dimension: 3,
+            cubies: self
+                .cubies
+                .iter()
+                .filter(|cubie| {
+                    cubie
+                        .home
+                        .iter()
+                        .all(|coordinate| coordinate.abs() == max || *coordinate == 0)
+                })
+                .map(|cubie| Cubie {
+                    home: reduce(cubie.home),
+                    position: reduce(cubie.position),
+                    rotation: cubie.rotation,
+                })
+                .collect(),
+        };
+
+        // all pieces are real ones, so only the centers can keep the corners and edges from
+        // being read relative to them
+        let cube = CubieCube::read_pieces(&reduced).ok_or(FaceletError::MirroredCenters)?;
+        let twist: u32 = cube
+            .corner_orientation
+            .iter()
+            .map(|&twist| twist as u32)
+            .sum();
+        let flip: u32 = cube.edge_orientation.iter().map(|&flip| flip as u32).sum();
+
+        if !twist.is_multiple_of(3) {
+            Err(FaceletError::TwistedCorner)
+        } else if !flip.is_multiple_of(2) {
+            Err(FaceletError::FlippedEdge)
+        } else if cube.corner_parity() != cube.edge_parity() {
+            Err(FaceletError::Parity)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks the twists of the corners of an even cube, whose centers can't tell how it is
+    /// held, which is why the twists are counted relative to the top and bottom colors.
+    fn check_corners(&self) -> Result<(), FaceletError> {
+        let max = self.dimension as i32 - 1;
+        let facelets = self.facelets();
+
+        let twist: usize = Corner::ALL
+            .into_iter()
+            .map(|corner| {
+                let faces = corner.faces();
+                let mut position = [0; 3];
+
+                for face in faces {
+                    position[face.axis().index()] = face.normal()[face.axis().index()] * max;
+                }
+
+                faces
+                    .iter()
+                    .position(|face| {
+                        let color = facelets[self.facelet_index(self.facelet_at(*face, position))];
+                        color.axis() == Axis::Y
+                    })
+                    .unwrap()
+            })
+            .sum();
+
+        if !twist.is_multiple_of(3) {
+            Err(FaceletError::TwistedCorner)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn dimension(&self) -> u32 {
+        self.dimension
+    }
+
+    pub fn cubies(&self) -> &[Cubie] {
+        &self.cubies
+    }
+
+    pub fn turn(&mut self, turn: &Turn) {
+        let rotation = Rotation::quarter_turns(turn.axis, turn.quarter_turns);
+
+        for cubie in self.cubies.iter_mut() {
+            let layer = Self::layer_of(self.dimension, cubie.position[turn.axis.index()]);
+
+            if turn.layers.contains(&layer) {
+                cubie.position = rotation.apply(cubie.position);
+                cubie.rotation = cubie.rotation.then(rotation);
+            }
+        }
+    }
+
+    /// Indices into [`RubikState::cubies`] of all cubies which are currently in the given layers.
+    pub fn cubies_in_layers(&self, axis: Axis, layers: RangeInclusive<u32>) -> Vec<usize> {
+        self.cubies
+            .iter()
+            .enumerate()
+            .filter(|(_, cubie)| {
+                layers.contains(&Self::layer_of(
+                    self.dimension,
+                    cubie.position[axis.index()],
+                ))
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Layer of the given axis the cubie with the given index currently lies in.
+    pub fn cubie_layer(&self, cubie: usize, axis: Axis) -> u32 {
+        Self::layer_of(self.dimension, self.cubies[cubie].position[axis.index()])
+    }
+
+    /// Layer of the given axis a cubie at the given position lies in.
+    pub fn layer_at(&self, position: [i32; 3], axis: Axis) -> u32 {
+        Self::layer_of(self.dimension, position[axis.index()])
+    }
+
+    /// Index into [`RubikState::cubies`] of the cubie which is currently at the given position.
+    pub fn cubie_at(&self, position: [i32; 3]) -> Option<usize> {
+        self.cubies
+            .iter()
+            .position(|cubie| cubie.position == position)
+    }
+
+    /// Colors of all stickers, each represented by the face they belong to in the solved state.
+    ///
+    /// The stickers are ordered by [`Face::ALL`] and then row by row within each face.
+    pub fn facelets(&self) -> Vec<Face> {
+        let n = self.dimension as usize;
+        let mut facelets = vec![Face::Right; Face::ALL.len() * n * n];
+
+        for cubie in &self.cubies {
+            for home_face in self.sticker_faces(cubie.home) {
+                let face = Face::from_normal(cubie.rotation.apply(home_face.normal()));
+                let facelet = self.facelet_at(face, cubie.position);
+                facelets[self.facelet_index(facelet)] = home_face;
+            }
+        }
+
+        facelets
+    }
+
+    /// The stickers as a string of face letters, with the faces ordered like
+    /// [`FACELET_STRING_ORDER`] and the stickers of each face row by row as seen in the usual cube
+    /// net, which for a 3x3x3 is the 54 character format of Kociemba's solver.
+    ///
+    /// Like in that format, the letter of a sticker names the face whose middle center has its
+    /// color. Even cubes have no such centers, so their letters name the face each sticker
+    /// belongs to in the solved state.
+    pub fn to_facelet_string(&self) -> String {
+        let facelets = self.facelets();
+        let n = self.dimension as usize;
+        let mut faces_by_color = Face::ALL;
+
+        if self.dimension % 2 == 1 {
+            let middle = (self.dimension / 2) as usize;
+
+            for face in Face::ALL {
+                let center = facelets[(face.index() * n + middle) * n + middle];
+                faces_by_color[center.index()] = face;
+            }
+        }
+
+        FACELET_STRING_ORDER
+            .into_iter()
+            .flat_map(|face| &facelets[face.index() * n * n..(face.index() + 1) * n * n])
+            .map(|color| faces_by_color[color.index()].letter())
+            .collect()
+    }
+
+    /// Reads a string written by [`RubikState::to_facelet_string`] or another program using the
+    /// same format. The dimension follows from its length.
+    pub fn from_facelet_string(facelet_string: &str) -> Result<Self, FaceletError> {
+        let letters = facelet_string
+            .chars()
+            .enumerate()
+            .map(|(index, letter)| {
+                Face::from_letter(letter).ok_or(FaceletError::UnknownLetter { letter, index })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let stickers_per_face = letters.len() / Face::ALL.len();
+        let n = (stickers_per_face as f64).sqrt().round() as usize;
+
+        if n == 0 || letters.len() != Face::ALL.len() * n * n {
+            return Err(FaceletError::StringLength(letters.len()));
+        }
+
+        let mut facelets = vec![Face::Right; letters.len()];
+
+        for (face, chunk) in FACELET_STRING_ORDER.into_iter().zip(letters.chunks(n * n)) {
+            facelets[face.index() * n * n..(face.index() + 1) * n * n].copy_from_slice(chunk);
+        }
+
+        Self::from_facelets(n as u32, &facelets)
+    }
+
+    /// Whether every face shows a single color.
+    ///
+    /// Only the stickers are compared, so rotations of the whole cube and, on even cubes, the
+    /// placement of the centers relative to each other don't matter.
+    pub fn is_solved(&self) -> bool {
+        let stickers_per_face = (self.dimension * self.dimension) as usize;
+
+        self.facelets()
+            .chunks(stickers_per_face)
+            .all(|face| face.iter().all(|color| *color == face[0]))
+    }
+
+    /// Color of a single sticker, represented by the face it belongs to in the solved state.
+    ///
+    /// Only looks at the cubie carrying the sticker, instead of all of them like
+    /// [`RubikState::facelets`].
+    pub fn facelet(&self, facelet: Facelet) -> Face {
+        let cubie = self
+            .cubie_at(self.facelet_position(facelet))
+            .map(|index| &self.cubies[index])
+            .expect("every sticker is carried by a cubie");
+
+        // the side of the cubie which got rotated onto the face of the sticker
+        Face::from_normal(cubie.rotation.inverse().apply(facelet.face.normal()))
+    }
+
+    pub fn facelet_index(&self, facelet: Facelet) -> usize {
+        let n = self.dimension as usize;
+        (facelet.face.index() * n + facelet.row as usize) * n + facelet.column as usize
+    }
+
+    /// The faces of a cubie at the given position which carry a sticker.
+    pub fn sticker_faces(&self, position: [i32; 3]) -> impl Iterator<Item = Face> {
+        let max = self.dimension as i32 - 1;
+
+        Face::ALL.into_iter().filter(move |face| {
+            let coordinate = position[face.axis().index()];
+            let sign = if face.is_positive() { 1 } else { -1 };
+            coordinate * sign == max
+        })
+    }
+
+    /// The sticker on the given face of a cubie at the given position.
+    pub fn facelet_at(&self, face: Face, position: [i32; 3]) -> Facelet {
+        let max = self.dimension as i32 - 1;
+        let (column_direction, row_direction) = face.grid_directions();
+        let project = |direction: [i32; 3]| {
+            let dot: i32 = direction.iter().zip(position).map(|(a, b)| a * b).sum();
+            ((dot + max) / 2) as u32
+        };
+
+        Facelet {
+            face,
+            row: project(row_direction),
+            column: project(column_direction),
+        }
+    }
+
+    /// Position of the cubie carrying the given sticker.
+    pub fn facelet_position(&self, facelet: Facelet) -> [i32; 3] {
+        let max = self.dimension as i32 - 1;
+        let (column_direction, row_direction) = facelet.face.grid_directions();
+        let normal = facelet.face.normal();
+        let column = 2 * facelet.column as i32 - max;
+        let row = 2 * facelet.row as i32 - max;
+
+        let mut position = [0; 3];
+
+        for (i, value) in position.iter_mut().enumerate() {
+            *value = normal[i] * max + column_direction[i] * column + row_direction[i] * row;
+        }
+
+        position
+    }
+
+    fn layer_of(dimension: u32, coordinate: i32) -> u32 {
+        ((dimension as i32 - 1 - coordinate) / 2) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle::Puzzle;
+
+    fn scrambled(dimension: u32, algorithm: &str) -> RubikState {
+        let mut state = RubikState::new(dimension);
+        let turns = Puzzle::parse_turns(&state, algorithm).unwrap();
+
+        for turn in &turns {
+            Puzzle::turn(&mut state, turn);
+        }
+
+        state
+    }
+
+    #[test]
+    fn single_facelets_match_all_facelets() {
+        for dimension in 1..=5 {
+            let state = scrambled(dimension, "R U' F2 L D B' x");
+            let facelets = state.facelets();
+
+            for face in Face::ALL {
+                for row in 0..dimension {
+                    for column in 0..dimension {
+                        let facelet = Facelet { face, row, column };
+                        assert_eq!(
+                            state.facelet(facelet),
+                            facelets[state.facelet_index(facelet)]
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    const SOLVED: &str = "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB";
+
+    /// The solved 3x3x3 with the letters at the given indices replaced.
+    fn solved_with(changes: &[(usize, char)]) -> String {
+        let mut letters = SOLVED.chars().collect::<Vec<_>>();
+
+        for &(index, letter) in changes {
+            letters[index] = letter;
+        }
+
+        letters.into_iter().collect()
+    }
+
+    #[test]
+    fn facelet_strings_round_trip() {
+        for dimension in 1..=6 {
+            let state = scrambled(dimension, "R U' F2 L D B' x y'");
+            let string = state.to_facelet_string();
+            assert_eq!(string.len(), 6 * (dimension * dimension) as usize);
+
+            let read = RubikState::from_facelet_string(&string).unwrap();
+            assert_eq!(read.to_facelet_string(), string);
+            assert_eq!(read.is_solved(), state.is_solved());
+        }
+
+        assert_eq!(RubikState::new(3).to_facelet_string(), SOLVED);
+    }
+
+    #[test]
+    fn facelet_strings_match_kociemba() {
+        let strings = [
+            (
+                "R",
+                "UUFUUFUUFRRRRRRRRRFFDFFDFFDDDBDDBDDBLLLLLLLLLUBBUBBUBB",
+            ),
+            (
+                "U",
+                "UUUUUUUUUBBBRRRRRRRRRFFFFFFDDDDDDDDDFFFLLLLLLLLLBBBBBB",
+            ),
+            (
+                "F",
+                "UUUUUULLLURRURRURRFFFFFFFFFRRRDDDDDDLLDLLDLLDBBBBBBBBB",
+            ),
+        ];
+
+        for (algorithm, string) in strings {
+            let state = scrambled(3, algorithm);
+            assert_eq!(state.to_facelet_string(), string, "{}", algorithm);
+
+            // the centers fit in any rotation, so only the stickers can be compared
+            let read = RubikState::from_facelet_string(string).unwrap();
+            assert_eq!(read.facelets(), state.facelets());
+        }
+    }
+
+    #[test]
+    fn invalid_facelets_are_rejected() {
+        let read = |string: &str| RubikState::from_facelet_string(string).unwrap_err();
+
+        assert_eq!(
+            RubikState::from_facelets(3, &[Face::Top; 10]).unwrap_err(),
+            FaceletError::Length {
+                expected: 54,
+                found: 10
+            }
+        );
+        assert_eq!(read("UUU"), FaceletError::StringLength(3));
+        assert_eq!(
+            read(&solved_with(&[(5, 'X')])),
+            FaceletError::UnknownLetter {
+                letter: 'X',
+                index: 5
+            }
+        );
+        assert!(matches!(
+            read(&solved_with(&[(0, 'R')])),
+            FaceletError::ColorCount { expected: 9, .. }
+        ));
+
+        // U9, R1 and F3 form the corner between U, R and F, U8 and F2 the edge between U and F
+        // and U6 and R2 the one between U and R
+        assert_eq!(
+            read(&solved_with(&[(8, 'R'), (9, 'F'), (20, 'U')])),
+            FaceletError::TwistedCorner
+        );
+        assert_eq!(
+            read(&solved_with(&[(7, 'F'), (19, 'U')])),
+            FaceletError::FlippedEdge
+        );
+        assert_eq!(
+            read(&solved_with(&[(19, 'R'), (10, 'F')])),
+            FaceletError::Parity
+        );
+        assert!(matches!(
+            read(&solved_with(&[(9, 'F'), (20, 'R')])),
+            FaceletError::ImpossiblePiece(_)
+        ));
+
+        // the centers of R and L swap places
+        assert_eq!(
+            read(&solved_with(&[(13, 'L'), (40, 'R')])),
+            FaceletError::MirroredCenters
+        );
+
+        // the edge between U and F shows up in place of the one between U and B, which makes up
+        // for its color by turning the edge between D and F into the one between D and B
+        assert!(matches!(
+            read(&solved_with(&[(46, 'F'), (25, 'B')])),
+            FaceletError::DuplicatePiece(_)
+        ));
+    }
+}
@@ -0,0 +1,579 @@
+use crate::notation::{Layers, Move, MoveKind};
+use crate::puzzle::pyraminx::state::Vertex;
+use crate::puzzle::pyraminx::{PyraminxLayers, PyraminxMove};
+use crate::puzzle::rubiks::cubie::{permutation_index, CubieCube};
+use crate::puzzle::rubiks::state::Face;
+use crate::puzzle::{Puzzle, PuzzleTurn};
+use crate::solver::kociemba::{Solver, DEFAULT_MAX_LENGTH};
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+
+/// Seedable SplitMix64 generator.
+///
+/// It is implemented here instead of pulling in a crate so that a seed keeps producing the same
+/// scramble on every platform and across dependency updates.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniformly distributed number in `0..bound`.
+    pub fn below(&mut self, bound: u32) -> u32 {
+        (((self.next_u64() >> 32) * bound as u64) >> 32) as u32
+    }
+
+    pub fn shuffle<T>(&mut self, slice: &mut [T]) {
+        for i in (1..slice.len()).rev() {
+            let j = self.below(i as u32 + 1) as usize;
+            slice.swap(i, j);
+        }
+    }
+}
+
+/// A fresh seed for when the scramble does not need to be reproduced.
+pub fn random_seed() -> u64 {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+
+    RandomState::new().build_hasher().finish()
+}
+
+/// Moves which mix up a solved puzzle, together with the seed that reproduces them.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Scramble<M> {
+    pub seed: u64,
+    pub moves: Vec<M>,
+}
+
+impl Scramble<Move> {
+    /// Random-state scramble for 2x2x2 and 3x3x3 and random-move scramble for bigger cubes.
+    ///
+    /// Scrambling a 3x3x3 the first time needs the tables of the [`Solver`].
+    pub fn rubik(dimension: u32, seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+
+        let moves = match dimension {
+            0 | 1 => Vec::new(),
+            2 => random_state_2x2(&mut rng),
+            3 => random_state_3x3(&mut rng),
+            _ => random_moves(dimension, 20 * (dimension as usize - 2), &mut rng),
+        };
+
+        Self { seed, moves }
+    }
+}
+
+impl Scramble<PyraminxMove> {
+    /// Random moves of the vertices followed by random tip turns.
+    ///
+    /// Bigger pyraminxes get longer scrambles, which turn a random number of layers from the
+    /// tip, so that their inner layers get mixed up as well.
+    pub fn pyraminx(dimension: u32, seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+
+        // the tips are turned at the end, so only the deeper layers need moves of their own
+        let deepest = dimension.saturating_sub(2);
+        let length = 11 * deepest as usize;
+        let mut moves: Vec<PyraminxMove> = Vec::with_capacity(length + Vertex::ALL.len());
+
+        while moves.len() < length {
+            let vertex = Vertex::ALL[rng.below(4) as usize];
+
+            if moves.last().is_some_and(|last| last.vertex == vertex) {
+                continue;
+            }
+
+            // a 3-layer pyraminx has no layers to choose from, so drawing nothing for it keeps
+            // the vertices its seeds draw the same as with only whole vertex moves
+            let last = match deepest {
+                1 => 1,
+                _ => 1 + rng.below(deepest),
+            };
+
+            moves.push(PyraminxMove {
+                vertex,
+                layers: if last == deepest {
+                    PyraminxLayers::Vertex
+                } else {
+                    PyraminxLayers::Range(1, last + 1)
+                },
+                amount: if rng.below(2) == 0 { 1 } else { -1 },
+            });
+        }
+
+        if dimension < 2 {
+            return Self { seed, moves };
+        }
+
+        for vertex in Vertex::ALL {
+            let amount = match rng.below(3) {
+                0 => continue,
+                1 => 1,
+                _ => -1,
+            };
+
+            moves.push(PyraminxMove {
+                vertex,
+                layers: PyraminxLayers::Tip,
+                amount,
+            });
+        }
+
+        Self { seed, moves }
+    }
+}
+
+impl Scramble<PuzzleTurn> {
+    /// Random [`Puzzle::legal_turns`] where no turn can cancel or merge with the turns before it.
+    pub fn random_turns<P: Puzzle + ?Sized>(puzzle: &P, length: usize, seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        let legal_turns = puzzle.legal_turns();
+        let mut turns: Vec<PuzzleTurn> = Vec::with_capacity(length);
+        // turns of the same axis commute, so none of them may turn the same layers again
+        let mut axis_turns: Vec<&PuzzleTurn> = Vec::new();
+
+        while turns.len() < length {
+            let allowed = legal_turns
+                .iter()
+                .filter(|turn| {
+                    axis_turns
+                        .first()
+                        .is_none_or(|first| first.axis != turn.axis)
+                        || axis_turns.iter().all(|other| other.layers != turn.layers)
+                })
+                .collect::<Vec<_>>();
+
+            let Some(&turn) = allowed.get(rng.below(allowed.len() as u32) as usize) else {
+                break;
+            };
+
+            if axis_turns
+                .first()
+                .is_some_and(|first| first.axis != turn.axis)
+            {
+                axis_turns.clear();
+            }
+
+            axis_turns.push(turn);
+            turns.push(turn.clone());
+        }
+
+        Self { seed, moves: turns }
+    }
+}
+
+impl<M: Display> Display for Scramble<M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, m) in self.moves.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+
+            write!(f, "{}", m)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn face_move(face: Face, layers: Layers, quarter_turns: i32) -> Move {
+    let amount = match quarter_turns.rem_euclid(4) {
+        3 => -1,
+        quarter_turns => quarter_turns,
+    };
+
+    Move {
+        kind: MoveKind::Face(face, layers),
+        amount,
+    }
+}
+
+/// Random outer and wide turns where no move can cancel or merge with the moves before it.
+fn random_moves(dimension: u32, length: usize, rng: &mut Rng) -> Vec<Move> {
+    let half = dimension / 2;
+    let mut moves = Vec::with_capacity(length);
+    // moves on the same axis commute, so none of them may turn the same layers again
+    let mut axis_moves: Vec<(Face, u32)> = Vec::new();
+
+    while moves.len() < length {
+        let face = Face::ALL[rng.below(6) as usize];
+        let depth = rng.below(half) + 1;
+
+        // turning both halves of an even cube is only a rotation
+        if dimension.is_multiple_of(2) && depth == half && !face.is_positive() {
+            continue;
+        }
+
+        if axis_moves
+            .first()
+            .is_some_and(|(f, _)| f.axis() != face.axis())
+        {
+            axis_moves.clear();
+        }
+
+        if axis_moves.contains(&(face, depth)) {
+            continue;
+        }
+
+        axis_moves.push((face, depth));
+
+        let layers = if depth == 1 {
+            Layers::Single(1)
+        } else {
+            Layers::Wide(depth)
+        };
+
+        moves.push(face_move(face, layers, rng.below(3) as i32 + 1));
+    }
+
+    moves
+}
+
+/// Faces that reach every state of a 2x2x2 while the `DBL` corner stays in place.
+const FACES_2X2: [Face; 3] = [Face::Top, Face::Right, Face::Front];
+
+/// Uniformly random 2x2x2 state, written as the inverse of its optimal solution.
+fn random_state_2x2(rng: &mut Rng) -> Vec<Move> {
+    const DBL: u8 = 6;
+
+    let tables = PruningTables2x2::new();
+
+    loop {
+        let mut cube = CubieCube::SOLVED;
+
+        let mut corners = [0, 1, 2, 3, 4, 5, 7];
+        rng.shuffle(&mut corners);
+
+        let mut twist = 0;
+
+        for (slot, corner) in [0, 1, 2, 3, 4, 5, 7].into_iter().zip(corners) {
+            cube.corner_permutation[slot] = corner;
+            cube.corner_orientation[slot] = rng.below(3) as u8;
+            twist += cube.corner_orientation[slot];
+        }
+
+        cube.corner_permutation[DBL as usize] = DBL;
+        cube.corner_orientation[DBL as usize] = 0;
+        // the twists of all corners add up to a multiple of three
+        cube.corner_orientation[7] = (cube.corner_orientation[7] + 3 - twist % 3) % 3;
+
+        let solution = tables.solve(&cube);
+
+        // WCA discards states which can be solved in less than four moves
+        if solution.len() < 4 {
+            continue;
+        }
+
+        return solution
+            .into_iter()
+            .rev()
+            .map(|(face, quarter_turns)| face_move(face, Layers::Single(1), -quarter_turns))
+            .collect();
+    }
+}
+
+/// Uniformly random 3x3x3 state, written as the inverse of a two-phase solution.
+fn random_state_3x3(rng: &mut Rng) -> Vec<Move> {
+    loop {
+        let mut cube = CubieCube::SOLVED;
+
+        rng.shuffle(&mut cube.corner_permutation);
+        rng.shuffle(&mut cube.edge_permutation);
+
+        // corners and edges are always swapped together
+        if cube.corner_parity() != cube.edge_parity() {
+            cube.edge_permutation.swap(0, 1);
+        }
+
+        for twist in &mut cube.corner_orientation[..7] {
+            *twist = rng.below(3) as u8;
+        }
+
+        for flip in &mut cube.edge_orientation[..11] {
+            *flip = rng.below(2) as u8;
+        }
+
+        let twist: u8 = cube.corner_orientation.iter().sum();
+        let flip: u8 = cube.edge_orientation.iter().sum();
+        cube.corner_orientation[7] = (3 - twist % 3) % 3;
+        cube.edge_orientation[11] = flip % 2;
+
+        let Ok(solution) = Solver::shared().solve(&cube, DEFAULT_MAX_LENGTH) else {
+            continue;
+        };
+
+        // WCA discards states which can be solved in less than two moves
+        if solution.len() < 2 {
+            continue;
+        }
+
+        return solution
+            .into_iter()
+            .rev()
+            .map(|m| if m.amount == 2 { m } else { m.inverse() })
+            .collect();
+    }
+}
+
+/// Distances to the solved state of the corner permutation and twist on their own, which are
+/// lower bounds for the distance of the whole 2x2x2.
+struct PruningTables2x2 {
+    permutation: Vec<u8>,
+    twist: Vec<u8>,
+}
+
+impl PruningTables2x2 {
+    fn new() -> Self {
+        Self {
+            permutation: Self::generate(40_320, corner_permutation_index),
+            twist: Self::generate(2187, twist_index),
+        }
+    }
+
+    fn generate(size: usize, index: fn(&CubieCube) -> usize) -> Vec<u8> {
+        let mut table = vec![u8::MAX; size];
+        let mut queue = VecDeque::from([CubieCube::SOLVED]);
+        table[index(&CubieCube::SOLVED)] = 0;
+
+        while let Some(cube) = queue.pop_front() {
+            let distance = table[index(&cube)];
+
+            for face in FACES_2X2 {
+                for quarter_turns in 1..=3 {
+                    let next = cube.turn(face, quarter_turns);
+                    let next_index = index(&next);
+
+                    if table[next_index] == u8::MAX {
+                        table[next_index] = distance + 1;
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+
+        table
+    }
+
+    fn lower_bound(&self, cube: &CubieCube) -> u8 {
+        self.permutation[corner_permutation_index(cube)].max(self.twist[twist_index(cube)])
+    }
+
+    /// Optimal solution as clockwise quarter turns of [`FACES_2X2`], found with IDA*.
+    fn solve(&self, cube: &CubieCube) -> Vec<(Face, i32)> {
+        let mut solution = Vec::new();
+        let mut depth = self.lower_bound(cube);
+
+        while !self.search(cube, depth, &mut solution) {
+            depth += 1;
+        }
+
+        solution
+    }
+
+    fn search(&self, cube: &CubieCube, depth: u8, solution: &mut Vec<(Face, i32)>) -> bool {
+        let lower_bound = self.lower_bound(cube);
+
+        if lower_bound == 0 {
+            return true;
+        }
+
+        if lower_bound > depth {
+            return false;
+        }
+
+        for face in FACES_2X2 {
+            if solution.last().is_some_and(|(last, _)| *last == face) {
+                continue;
+            }
+
+            for quarter_turns in 1..=3 {
+                solution.push((face, quarter_turns));
+
+                if self.search(&cube.turn(face, quarter_turns), depth - 1, solution) {
+                    return true;
+                }
+
+                solution.pop();
+            }
+        }
+
+        false
+    }
+}
+
+fn corner_permutation_index(cube: &CubieCube) -> usize {
+    permutation_index(&cube.corner_permutation)
+}
+
+/// Twists of all but the last corner read as a number in base three.
+fn twist_index(cube: &CubieCube) -> usize {
+    cube.corner_orientation[..7]
+        .iter()
+        .fold(0, |index, &twist| index * 3 + twist as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle::mirror::state::MirrorState;
+    use crate::puzzle::pyraminx::parse_moves;
+    use crate::puzzle::pyraminx::state::PyraminxState;
+    use crate::puzzle::rubiks::state::{RubikState, Turn};
+
+    fn apply(dimension: u32, moves: &[Move]) -> RubikState {
+        let mut state = RubikState::new(dimension);
+
+        for m in moves {
+            state.turn(
+                &m.to_turn(dimension)
+                    .expect("scrambles only turn existing layers"),
+            );
+        }
+
+        state
+    }
+
+    #[test]
+    fn same_seed_gives_same_scramble() {
+        for dimension in 2..=7 {
+            assert_eq!(
+                Scramble::rubik(dimension, 42),
+                Scramble::rubik(dimension, 42)
+            );
+            assert_ne!(Scramble::rubik(dimension, 1), Scramble::rubik(dimension, 2));
+        }
+
+        for dimension in 2..=7 {
+            assert_eq!(
+                Scramble::pyraminx(dimension, 42),
+                Scramble::pyraminx(dimension, 42)
+            );
+        }
+    }
+
+    #[test]
+    fn pyraminx_scrambles_turn_every_layer() {
+        for dimension in 3..=7 {
+            let scramble = Scramble::pyraminx(dimension, 7);
+            assert!(scramble.moves.len() >= 11 * (dimension as usize - 2));
+
+            let mut state = PyraminxState::new(dimension);
+            let mut turned = vec![false; dimension as usize - 1];
+
+            for m in &scramble.moves {
+                let turn = m.to_turn(dimension).unwrap();
+
+                for layer in turn.layers.clone() {
+                    turned[layer as usize] = true;
+                }
+
+                state.turn(&turn);
+            }
+
+            assert!(turned.iter().all(|&turned| turned), "{}", scramble);
+            assert!(!state.is_solved());
+            assert_eq!(parse_moves(&scramble.to_string()).unwrap(), scramble.moves);
+        }
+    }
+
+    #[test]
+    fn random_state_scrambles_reach_valid_unsolved_states() {
+        for dimension in [2, 3] {
+            for seed in 0..10 {
+                let state = apply(dimension, &Scramble::rubik(dimension, seed).moves);
+                assert!(!state.is_solved());
+
+                // reading the stickers back checks that the pieces form a reachable state
+                let read = RubikState::from_facelets(dimension, &state.facelets()).unwrap();
+                assert_eq!(read.facelets(), state.facelets());
+            }
+        }
+    }
+
+    #[test]
+    fn random_moves_never_turn_the_same_layers_of_an_axis_twice() {
+        for dimension in 4..=7 {
+            for seed in 0..20 {
+                let scramble = Scramble::rubik(dimension, seed);
+                assert_eq!(scramble.moves.len(), 20 * (dimension as usize - 2));
+
+                let mut axis_turns = Vec::new();
+
+                for m in &scramble.moves {
+                    let turn = m.to_turn(dimension).unwrap();
+
+                    if axis_turns
+                        .first()
+                        .is_some_and(|first: &Turn| first.axis != turn.axis)
+                    {
+                        axis_turns.clear();
+                    }
+
+                    assert!(
+                        axis_turns.iter().all(|other| other.layers != turn.layers),
+                        "{} turns the layers of {} again in {}",
+                        m,
+                        turn.axis.index(),
+                        scramble
+                    );
+
+                    axis_turns.push(turn);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn random_turns_are_legal_and_never_turn_the_same_layers_of_an_axis_twice() {
+        let puzzles: [Box<dyn Puzzle>; 3] = [
+            Box::new(RubikState::new(4)),
+            Box::new(PyraminxState::new(4)),
+            Box::new(MirrorState::new()),
+        ];
+
+        for mut puzzle in puzzles {
+            let legal_turns = puzzle.legal_turns();
+
+            for seed in 0..10 {
+                let scramble = Scramble::random_turns(puzzle.as_ref(), 30, seed);
+                assert_eq!(scramble.moves.len(), 30);
+
+                let mut axis_turns: Vec<&PuzzleTurn> = Vec::new();
+
+                for turn in &scramble.moves {
+                    assert!(legal_turns.contains(turn));
+
+                    if axis_turns
+                        .first()
+                        .is_some_and(|first| first.axis != turn.axis)
+                    {
+                        axis_turns.clear();
+                    }
+
+                    assert!(axis_turns.iter().all(|other| other.layers != turn.layers));
+                    axis_turns.push(turn);
+                }
+            }
+
+            for turn in &puzzle.scramble(3).moves {
+                puzzle.turn(turn);
+            }
+
+            assert!(!puzzle.is_solved());
+        }
+    }
+}
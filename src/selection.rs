@@ -0,0 +1,288 @@
+use crate::history::History;
+use crate::puzzle::{ColorSchemes, CurrentPuzzle, PuzzleKind, PuzzleRender, Styles};
+use crate::turn::{Piece, PieceEntities, TurnQueue};
+use crate::view::View;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Keys which select a puzzle with its default dimension.
+const PUZZLE_KEYS: [(KeyCode, PuzzleKind); 3] = [
+    (KeyCode::F1, PuzzleKind::Rubik),
+    (KeyCode::F2, PuzzleKind::Pyraminx),
+    (KeyCode::F3, PuzzleKind::Mirror),
+];
+
+const BIGGER_KEY: KeyCode = KeyCode::PageUp;
+const SMALLER_KEY: KeyCode = KeyCode::PageDown;
+const COLORS_KEY: KeyCode = KeyCode::F4;
+const STYLE_KEY: KeyCode = KeyCode::F6;
+
+/// Keys selecting the puzzle, its colors and its style.
+pub const RESERVED_KEYS: [KeyCode; 7] = [
+    PUZZLE_KEYS[0].0,
+    PUZZLE_KEYS[1].0,
+    PUZZLE_KEYS[2].0,
+    BIGGER_KEY,
+    SMALLER_KEY,
+    COLORS_KEY,
+    STYLE_KEY,
+];
+
+pub struct SelectionPlugin {
+    puzzle: ActivePuzzle,
+}
+
+impl SelectionPlugin {
+    pub fn new(puzzle: ActivePuzzle) -> Self {
+        Self { puzzle }
+    }
+}
+
+impl Plugin for SelectionPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.puzzle)
+            .init_resource::<SpawnedPuzzle>()
+            .init_resource::<ColorSchemes>()
+            .init_resource::<Styles>()
+            .add_system(select_with_keyboard.label("select_puzzle"))
+            .add_system(
+                spawn_active_puzzle
+                    .label("spawn_puzzle")
+                    .after("select_puzzle"),
+            )
+            .add_system(update_colors.after("spawn_puzzle"));
+    }
+}
+
+/// The puzzle which is shown, changing it replaces the spawned puzzle with one in the state the
+/// [`History`] describes. So does changing the [`Styles`], as the meshes depend on them.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Resource, Serialize, Deserialize)]
+pub struct ActivePuzzle {
+    pub kind: PuzzleKind,
+    pub dimension: u32,
+}
+
+impl ActivePuzzle {
+    /// The puzzle with its default dimension.
+    pub fn new(kind: PuzzleKind) -> Self {
+        Self {
+            kind,
+            dimension: kind.entry().default_dimension,
+        }
+    }
+
+    /// Same puzzle with the dimension changed by `delta`, as far as the puzzle allows it.
+    pub fn resized(&self, delta: i32) -> Self {
+        let dimensions = &self.kind.entry().dimensions;
+        let dimension = (self.dimension as i32 + delta)
+            .clamp(*dimensions.start() as i32, *dimensions.end() as i32);
+
+        Self {
+            dimension: dimension as u32,
+            ..*self
+        }
+    }
+}
+
+/// Assets of the spawned puzzle, so that they can be freed once it is replaced.
+#[derive(Debug, Default, Resource)]
+pub struct SpawnedPuzzle {
+    /// The meshes the pieces share, in the order of [`PuzzleRender::create_meshes`].
+    pub meshes: Vec<Handle<Mesh>>,
+    /// The meshes of the chunks if the puzzle is drawn in [`crate::puzzle::Chunks`], which get replaced in
+    /// place when the chunks change.
+    pub chunks: Vec<Handle<Mesh>>,
+    pub material: Option<Handle<StandardMaterial>>,
+    texture: Option<Handle<Image>>,
+}
+
+fn select_with_keyboard(
+    mut active_puzzle: ResMut<ActivePuzzle>,
+    mut color_schemes: ResMut<ColorSchemes>,
+    mut styles: ResMut<Styles>,
+    mut history: ResMut<History>,
+    keyboard_input: Res<Input<KeyCode>>,
+) {
+    let mut selected = *active_puzzle;
+
+    for (key, kind) in PUZZLE_KEYS {
+        if keyboard_input.just_pressed(key) && kind != selected.kind {
+            selected = ActivePuzzle::new(kind);
+        }
+    }
+
+    if keyboard_input.just_pressed(BIGGER_KEY) {
+        selected = selected.resized(1);
+    }
+
+    if keyboard_input.just_pressed(SMALLER_KEY) {
+        selected = selected.resized(-1);
+    }
+
+    // only assign on a change, because every assignment would respawn the puzzle
+    if selected != *active_puzzle {
+        *active_puzzle = selected;
+        // the newly selected puzzle starts out solved
+        history.clear();
+    }
+
+    if keyboard_input.just_pressed(COLORS_KEY) {
+        let name = color_schemes.cycle(selected.kind);
+        info!("switched to the {} colors", name);
+    }
+
+    if keyboard_input.just_pressed(STYLE_KEY) {
+        match styles.cycle(selected.kind) {
+            Some(name) => info!("switched to the {} style", name),
+            None => info!("the {} has no other styles", selected.kind.entry().name),
+        }
+    }
+}
+
+fn spawn_active_puzzle(
+    mut commands: Commands,
+    mut spawned_puzzle: ResMut<SpawnedPuzzle>,
+    mut turn_queue: ResMut<TurnQueue>,
+    mut history: ResMut<History>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+    active_puzzle: Res<ActivePuzzle>,
+    color_schemes: Res<ColorSchemes>,
+    styles: Res<Styles>,
+    view_query: Query<Entity, With<View>>,
+) {
+    if !active_puzzle.is_changed() && !styles.is_changed() {
+        return;
+    }
+
+    let view_entity = view_query.single();
+
+    // turns in progress belong to the pieces which are about to be despawned
+    turn_queue.clear();
+    commands.entity(view_entity).despawn_descendants();
+
+    for mesh in spawned_puzzle.meshes.drain(..) {
+        meshes.remove(mesh);
+    }
+
+    for mesh in spawned_puzzle.chunks.drain(..) {
+        meshes.remove(mesh);
+    }
+
+    if let Some(material) = spawned_puzzle.material.take() {
+        materials.remove(material);
+    }
+
+    if let Some(texture) = spawned_puzzle.texture.take() {
+        images.remove(texture);
+    }
+
+    let entry = active_puzzle.kind.entry();
+    let mut puzzle = (entry.create_render)(active_puzzle.dimension);
+    puzzle.set_color_schemes(&color_schemes);
+    puzzle.set_styles(&styles);
+
+    // the puzzle continues where the history left off, e.g. when a session got loaded
+    if let Some(start) = history.start() {
+        let painted = puzzle.paint_mut().map(|paint| paint.set_stickers(start));
+
+        if !matches!(painted, Some(Ok(()))) {
+            warn!("the history doesn't start with a state of the selected puzzle");
+            history.clear();
+        }
+    }
+
+    for turn in history.raw() {
+        puzzle.turn(turn);
+    }
+
+    let texture = images.add(puzzle.create_texture());
+    let material = materials.add(puzzle.create_material(texture.clone()));
+    let mut piece_entities = Vec::new();
+
+    if let Some(chunks) = puzzle.chunks() {
+        // far too many pieces to spawn, so only the chunks get an entity
+        let all_chunks = (0..chunks.chunk_count()).collect::<Vec<_>>();
+
+        spawned_puzzle.chunks = chunks
+            .create_chunk_meshes(&all_chunks, None)
+            .into_iter()
+            .map(|mesh| meshes.add(mesh))
+            .collect();
+
+        commands.entity(view_entity).add_children(|builder| {
+            for mesh in &spawned_puzzle.chunks {
+                builder.spawn(PbrBundle {
+                    mesh: mesh.clone(),
+                    material: material.clone(),
+                    ..default()
+                });
+            }
+        });
+    } else {
+        spawned_puzzle.meshes = puzzle
+            .create_meshes()
+            .into_iter()
+            .map(|mesh| meshes.add(mesh))
+            .collect();
+
+        commands.entity(view_entity).add_children(|builder| {
+            for (index, mesh) in puzzle.piece_meshes().into_iter().enumerate() {
+                let entity = builder
+                    .spawn((
+                        PbrBundle {
+                            transform: puzzle.get_piece_transform(index),
+                            mesh: spawned_puzzle.meshes[mesh].clone(),
+                            material: material.clone(),
+                            ..default()
+                        },
+                        Piece(index),
+                    ))
+                    .id();
+
+                piece_entities.push(entity);
+            }
+        });
+    }
+
+    spawned_puzzle.material = Some(material);
+    spawned_puzzle.texture = Some(texture);
+
+    commands.insert_resource(PieceEntities(piece_entities));
+    commands.insert_resource(CurrentPuzzle(puzzle));
+
+    info!(
+        "selected {} with dimension {}",
+        entry.name, active_puzzle.dimension
+    );
+}
+
+/// Swaps the colors of the palette texture, so that the meshes don't need to be respawned.
+fn update_colors(
+    mut images: ResMut<Assets<Image>>,
+    puzzle: Option<ResMut<CurrentPuzzle>>,
+    spawned_puzzle: Res<SpawnedPuzzle>,
+    active_puzzle: Res<ActivePuzzle>,
+    color_schemes: Res<ColorSchemes>,
+) {
+    // a newly selected puzzle already gets spawned with the current colors
+    if !color_schemes.is_changed() || active_puzzle.is_changed() {
+        return;
+    }
+
+    let Some(mut puzzle) = puzzle else {
+        return;
+    };
+
+    puzzle.0.set_color_schemes(&color_schemes);
+
+    let texture = spawned_puzzle
+        .texture
+        .as_ref()
+        .and_then(|texture| images.get_mut(texture));
+
+    if let Some(texture) = texture {
+        *texture = puzzle.0.create_texture();
+    }
+}
@@ -0,0 +1,346 @@
+use crate::history::History;
+use crate::puzzle::{ColorSchemes, Styles};
+use crate::selection::ActivePuzzle;
+use crate::view::{View, ViewRotation};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+/// Saves the session while control is held.
+const SAVE_KEY: KeyCode = KeyCode::S;
+/// Loads the session while control is held.
+const LOAD_KEY: KeyCode = KeyCode::O;
+
+/// Version written into saved sessions, which gets increased whenever the format changes in a
+/// way older sessions can't be read with anymore.
+pub const CURRENT_VERSION: u32 = 1;
+
+/// Name of the file in the working directory sessions are saved to by default.
+pub const DEFAULT_FILE_NAME: &str = "session.json";
+
+pub struct SessionPlugin {
+    path: PathBuf,
+}
+
+impl SessionPlugin {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl Plugin for SessionPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SessionFile(self.path.clone()))
+            .add_system(save_and_load_with_keyboard.before("select_puzzle"));
+    }
+}
+
+/// The file sessions are saved to and loaded from.
+#[derive(Debug, Clone, Resource)]
+pub struct SessionFile(pub PathBuf);
+
+/// Everything needed to continue with the puzzle later on.
+///
+/// Fields added within the same version need a default, so that sessions which were saved
+/// before they existed can still be read.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub version: u32,
+    pub puzzle: ActivePuzzle,
+    pub color_schemes: ColorSchemes,
+    /// The turns together with the state they started from, which the state of the puzzle is
+    /// restored with.
+    pub history: History,
+    #[serde(default)]
+    pub view: ViewRotation,
+    #[serde(default)]
+    pub camera: Option<CameraTransform>,
+    #[serde(default)]
+    pub styles: Styles,
+}
+
+/// Where the camera is and where it looks at, which only changes with the debug camera.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CameraTransform {
+    pub translation: [f32; 3],
+    pub rotation: [f32; 4],
+}
+
+impl From<Transform> for CameraTransform {
+    fn from(transform: Transform) -> Self {
+        Self {
+            translation: transform.translation.to_array(),
+            rotation: transform.rotation.to_array(),
+        }
+    }
+}
+
+impl From<CameraTransform> for Transform {
+    fn from(camera: CameraTransform) -> Self {
+        Transform::from_translation(Vec3::from_array(camera.translation))
+            .with_rotation(Quat::from_array(camera.rotation).normalize())
+    }
+}
+
+#[derive(Debug)]
+pub enum SessionError {
+    Io(std::io::Error),
+    Format(serde_json::Error),
+    /// The session was saved by a newer version of the app.
+    UnsupportedVersion(u32),
+    /// The puzzle can't be created with the saved dimension.
+    WrongDimension(u32),
+    /// The history contains turns the puzzle can't do.
+    InvalidHistory,
+}
+
+impl Display for SessionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SessionError::Io(error) => write!(f, "{}", error),
+            SessionError::Format(error) => write!(f, "the session is malformed: {}", error),
+            SessionError::UnsupportedVersion(version) => write!(
+                f,
+                "the session has version {} but only up to version {} is supported",
+                version, CURRENT_VERSION
+            ),
+            SessionError::WrongDimension(dimension) => {
+                write!(f, "the puzzle doesn't exist with dimension {}", dimension)
+            }
+            SessionError::InvalidHistory => {
+                write!(f, "the history doesn't fit the puzzle")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SessionError {}
+
+impl From<std::io::Error> for SessionError {
+    fn from(error: std::io::Error) -> Self {
+        SessionError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for SessionError {
+    fn from(error: serde_json::Error) -> Self {
+        SessionError::Format(error)
+    }
+}
+
+impl Session {
+    pub fn to_json(&self) -> Result<String, SessionError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Reads a session of the current or an older version, which only gets accepted if its
+    /// history fits its puzzle.
+    pub fn from_json(json: &str) -> Result<Self, SessionError> {
+        // the version decides how the rest gets read
+        #[derive(Deserialize)]
+        struct Version {
+            version: u32,
+        }
+
+        let Version { version } = serde_json::from_str(json)?;
+
+        // sessions of older versions get upgraded here, once there are any
+        let session: Session = match version {
+            CURRENT_VERSION => serde_json::from_str(json)?,
+            _ => return Err(SessionError::UnsupportedVersion(version)),
+        };
+
+        session.validate()?;
+        Ok(session)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), SessionError> {
+        Ok(std::fs::write(path, self.to_json()?)?)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, SessionError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    fn validate(&self) -> Result<(), SessionError> {
+        let entry = self.puzzle.kind.entry();
+
+        if !entry.dimensions.contains(&self.puzzle.dimension) {
+            return Err(SessionError::WrongDimension(self.puzzle.dimension));
+        }
+
+        let puzzle = (entry.create)(self.puzzle.dimension);
+
+        if !self.history.fits(puzzle.as_ref()) {
+            return Err(SessionError::InvalidHistory);
+        }
+
+        Ok(())
+    }
+}
+
+/// Loading replaces the puzzle, which then gets spawned in the state of the loaded history.
+fn save_and_load_with_keyboard(
+    mut active_puzzle: ResMut<ActivePuzzle>,
+    mut color_schemes: ResMut<ColorSchemes>,
+    mut styles: ResMut<Styles>,
+    mut history: ResMut<History>,
+    mut view_rotation: ResMut<ViewRotation>,
+    session_file: Res<SessionFile>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut view_query: Query<&mut Transform, With<View>>,
+    mut camera_query: Query<&mut Transform, (With<Camera>, Without<View>)>,
+) {
+    if !keyboard_input.any_pressed([KeyCode::LControl, KeyCode::RControl]) {
+        return;
+    }
+
+    let path = &session_file.0;
+
+    if keyboard_input.just_pressed(SAVE_KEY) {
+        let session = Session {
+            version: CURRENT_VERSION,
+            puzzle: *active_puzzle,
+            color_schemes: *color_schemes,
+            history: history.clone(),
+            view: *view_rotation,
+            camera: camera_query
+                .get_single()
+                .ok()
+                .map(|transform| CameraTransform::from(*transform)),
+            styles: *styles,
+        };
+
+        match session.save(path) {
+            Ok(()) => info!("saved the session to {}", path.display()),
+            Err(error) => warn!("can't save the session to {}: {}", path.display(), error),
+        }
+    } else if keyboard_input.just_pressed(LOAD_KEY) {
+        let session = match Session::load(path) {
+            Ok(session) => session,
+            Err(error) => {
+                warn!("can't load the session from {}: {}", path.display(), error);
+                return;
+            }
+        };
+
+        // always assigned, so that the puzzle gets respawned even if it stays the same
+        *active_puzzle = session.puzzle;
+        *color_schemes = session.color_schemes;
+        *styles = session.styles;
+        *history = session.history;
+        *view_rotation = session.view;
+
+        if let Ok(mut transform) = view_query.get_single_mut() {
+            transform.rotation = view_rotation.to_quat();
+        }
+
+        if let (Some(camera), Ok(mut transform)) = (session.camera, camera_query.get_single_mut()) {
+            *transform = camera.into();
+        }
+
+        info!("loaded the session from {}", path.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle::{PuzzleKind, PuzzleTurn};
+
+    fn session() -> Session {
+        let mut history = History::default();
+        history.record(PuzzleTurn {
+            axis: 0,
+            layers: 0..=0,
+            amount: 1,
+        });
+
+        Session {
+            version: CURRENT_VERSION,
+            puzzle: ActivePuzzle {
+                kind: PuzzleKind::Rubik,
+                dimension: 3,
+            },
+            color_schemes: ColorSchemes::default(),
+            history,
+            view: ViewRotation {
+                pitch: 0.5,
+                yaw: -1.0,
+            },
+            camera: Some(CameraTransform {
+                translation: [1.0, 2.0, 3.0],
+                rotation: [0.0, 0.0, 0.0, 1.0],
+            }),
+            styles: Styles::default(),
+        }
+    }
+
+    fn with_field(session: &Session, field: &str, value: serde_json::Value) -> String {
+        let mut json = serde_json::to_value(session).unwrap();
+        json[field] = value;
+        json.to_string()
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let session = session();
+        let loaded = Session::from_json(&session.to_json().unwrap()).unwrap();
+
+        assert_eq!(loaded, session);
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let json = with_field(&session(), "version", (CURRENT_VERSION + 1).into());
+
+        assert!(matches!(
+            Session::from_json(&json),
+            Err(SessionError::UnsupportedVersion(version)) if version == CURRENT_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn rejects_histories_not_fitting_the_puzzle() {
+        let mut session = session();
+        session.history.record(PuzzleTurn {
+            axis: 0,
+            layers: 2..=3,
+            amount: 1,
+        });
+
+        assert!(matches!(
+            Session::from_json(&session.to_json().unwrap()),
+            Err(SessionError::InvalidHistory)
+        ));
+
+        let json = with_field(
+            &session,
+            "puzzle",
+            serde_json::json!({"kind": "Rubik", "dimension": 0}),
+        );
+
+        assert!(matches!(
+            Session::from_json(&json),
+            Err(SessionError::WrongDimension(0))
+        ));
+    }
+
+    #[test]
+    fn fields_with_defaults_can_be_missing() {
+        let session = session();
+        let mut json = serde_json::to_value(&session).unwrap();
+
+        for field in ["view", "camera", "styles"] {
+            json.as_object_mut().unwrap().remove(field);
+        }
+
+        let loaded = Session::from_json(&json.to_string()).unwrap();
+
+        assert_eq!(loaded.view, ViewRotation::default());
+        assert_eq!(loaded.camera, None);
+        assert_eq!(loaded.styles, Styles::default());
+        assert_eq!(loaded.history, session.history);
+    }
+}
@@ -0,0 +1,39 @@
+use crate::puzzle::rubiks::cubie::CubieCube;
+use crate::puzzle::rubiks::state::RubikState;
+use std::fmt::{Display, Formatter};
+
+pub mod beginner;
+pub mod kociemba;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SolveError {
+    /// Only 3x3x3 cubes can be solved.
+    WrongDimension(u32),
+    /// The stickers don't describe a state that can be reached by turning.
+    Unsolvable,
+    /// There is no solution within the maximum length.
+    NotFound,
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveError::WrongDimension(dimension) => {
+                write!(f, "can only solve a 3x3x3 and not a {0}x{0}x{0}", dimension)
+            }
+            SolveError::Unsolvable => write!(f, "the cube can't be solved"),
+            SolveError::NotFound => write!(f, "no solution within the maximum length"),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// The pieces of a 3x3x3, relative to its centers, which is what the solvers work with.
+fn to_cubie_cube(state: &RubikState) -> Result<CubieCube, SolveError> {
+    if state.dimension() != 3 {
+        return Err(SolveError::WrongDimension(state.dimension()));
+    }
+
+    CubieCube::from_state(state).ok_or(SolveError::Unsolvable)
+}
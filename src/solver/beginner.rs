@@ -0,0 +1,595 @@
+use crate::notation::{merge_moves, Algorithm, Layers, Move, MoveKind};
+use crate::puzzle::rubiks::cubie::{Corner, CubieCube, Edge};
+use crate::puzzle::rubiks::state::{Face, RubikState};
+use crate::solver::{to_cubie_cube, SolveError};
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{Display, Formatter};
+
+/// Front and right face of each bottom corner slot and the middle layer edge slot above it,
+/// which is how the algorithms written for the front right slot are applied to the others.
+const SLOT_FRAMES: [(Face, Face); 4] = [
+    (Face::Front, Face::Right),
+    (Face::Left, Face::Front),
+    (Face::Back, Face::Left),
+    (Face::Right, Face::Back),
+];
+
+/// Cycles the front right bottom corner through its twists while only touching the top layer
+/// and the front right middle layer edge.
+const CORNER_INSERT: &str = "R U R' U'";
+/// Inserts the edge from the top front into the front right slot.
+const RIGHT_EDGE_INSERT: &str = "U R U' R' U' F' U F";
+/// Inserts the edge from the top right into the front right slot.
+const LEFT_EDGE_INSERT: &str = "U' F' U F U R U' R'";
+const EDGE_ORIENTATION: &str = "F R U R' U' F'";
+/// Sune, which twists three corners of the top layer.
+const CORNER_ORIENTATION: &str = "R U R' U R U2 R'";
+/// A permutation, which cycles three corners of the top layer.
+const CORNER_PERMUTATION: &str = "R' F R' B2 R F' R' B2 R2";
+/// U permutation, which cycles three edges of the top layer.
+const EDGE_PERMUTATION: &str = "R U' R U R U R U' R' U' R2";
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum StageKind {
+    Cross,
+    FirstLayerCorners,
+    SecondLayer,
+    /// Orientation of the last layer.
+    Oll,
+    /// Permutation of the last layer.
+    Pll,
+}
+
+impl Display for StageKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StageKind::Cross => write!(f, "Cross"),
+            StageKind::FirstLayerCorners => write!(f, "First layer corners"),
+            StageKind::SecondLayer => write!(f, "Second layer"),
+            StageKind::Oll => write!(f, "OLL"),
+            StageKind::Pll => write!(f, "PLL"),
+        }
+    }
+}
+
+/// Moves with a single purpose, like inserting one piece.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Step {
+    pub description: String,
+    pub moves: Vec<Move>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Stage {
+    pub kind: StageKind,
+    pub steps: Vec<Step>,
+}
+
+impl Stage {
+    /// All moves of the stage.
+    pub fn moves(&self) -> Vec<Move> {
+        let moves = self
+            .steps
+            .iter()
+            .flat_map(|step| step.moves.iter().copied())
+            .collect::<Vec<_>>();
+
+        merge_moves(&moves)
+    }
+}
+
+/// Solves the cube layer by layer the way beginners learn it, with the cross on the bottom.
+pub fn solve_state(state: &RubikState) -> Result<Vec<Stage>, SolveError> {
+    solve(&to_cubie_cube(state)?)
+}
+
+pub fn solve(cube: &CubieCube) -> Result<Vec<Stage>, SolveError> {
+    if !cube.is_solvable() {
+        return Err(SolveError::Unsolvable);
+    }
+
+    let mut solver = BeginnerSolver { cube: *cube };
+
+    Ok(vec![
+        Stage {
+            kind: StageKind::Cross,
+            steps: vec![solver.cross()],
+        },
+        Stage {
+            kind: StageKind::FirstLayerCorners,
+            steps: (4..8)
+                .flat_map(|slot| solver.first_layer_corner(slot))
+                .collect(),
+        },
+        Stage {
+            kind: StageKind::SecondLayer,
+            steps: (8..12)
+                .flat_map(|slot| solver.second_layer_edge(slot))
+                .collect(),
+        },
+        Stage {
+            kind: StageKind::Oll,
+            steps: solver.orient_last_layer(),
+        },
+        Stage {
+            kind: StageKind::Pll,
+            steps: solver.permute_last_layer(),
+        },
+    ])
+}
+
+struct BeginnerSolver {
+    cube: CubieCube,
+}
+
+impl BeginnerSolver {
+    fn apply(&mut self, description: String, moves: Vec<Move>) -> Step {
+        self.cube = apply(&self.cube, &moves);
+
+        Step {
+            description,
+            moves: merge_moves(&moves),
+        }
+    }
+
+    /// Solves the bottom edges optimally, using the distances of all their placements.
+    fn cross(&mut self) -> Step {
+        let turns = turns();
+        let edge_turns = edge_turns();
+        let distances = cross_distances(&edge_turns);
+
+        let mut placements = cross_placements(&self.cube);
+        let mut moves = Vec::new();
+
+        while distances[cross_index(placements)] > 0 {
+            let distance = distances[cross_index(placements)];
+
+            let ((face, quarter_turns), next) = turns
+                .into_iter()
+                .zip(&edge_turns)
+                .map(|(turn, edge_turn)| (turn, placements.map(|placement| edge_turn[placement])))
+                .find(|(_, next)| distances[cross_index(*next)] < distance)
+                .unwrap();
+
+            moves.push(face_move(face, quarter_turns));
+            placements = next;
+        }
+
+        self.apply("Solve the bottom edges".to_string(), moves)
+    }
+
+    fn first_layer_corner(&mut self, slot: usize) -> Vec<Step> {
+        let mut steps = Vec::new();
+        let name = Corner::ALL[slot];
+        let current = self.corner_slot(slot);
+
+        // a corner twisted in its own slot is solved by repeating the insert without a U turn
+        if (4..8).contains(&current) && current != slot {
+            let (front, right) = SLOT_FRAMES[current - 4];
+
+            steps.push(self.apply(
+                format!(
+                    "Take the {:?} corner out of the {:?} slot",
+                    name,
+                    Corner::ALL[current]
+                ),
+                frame(CORNER_INSERT, front, right),
+            ));
+        }
+
+        if is_corner_solved(&self.cube, slot) {
+            return steps;
+        }
+
+        let (front, right) = SLOT_FRAMES[slot - 4];
+        let insert = frame(CORNER_INSERT, front, right);
+
+        let moves = (0..4)
+            .flat_map(|u_turns| (1..=5).map(move |repetitions| (u_turns, repetitions)))
+            .map(|(u_turns, repetitions)| {
+                let mut moves = u_turn(u_turns);
+                moves.extend(insert.iter().cycle().take(insert.len() * repetitions));
+                moves
+            })
+            .find(|moves| is_corner_solved(&apply(&self.cube, moves), slot))
+            .unwrap();
+
+        steps.push(self.apply(
+            format!(
+                "Move the {:?} corner above its slot and repeat {} until it is solved",
+                name,
+                format_moves(&insert)
+            ),
+            moves,
+        ));
+
+        steps
+    }
+
+    fn second_layer_edge(&mut self, slot: usize) -> Vec<Step> {
+        let mut steps = Vec::new();
+        let name = Edge::ALL[slot];
+        let current = self.edge_slot(slot);
+
+        if (8..12).contains(&current) && !is_edge_solved(&self.cube, slot) {
+            let (front, right) = SLOT_FRAMES[current - 8];
+
+            let description = if current == slot {
+                format!("Take the {:?} edge out of its slot to flip it", name)
+            } else {
+                format!(
+                    "Take the {:?} edge out of the {:?} slot",
+                    name,
+                    Edge::ALL[current]
+                )
+            };
+
+            steps.push(self.apply(description, frame(RIGHT_EDGE_INSERT, front, right)));
+        }
+
+        if is_edge_solved(&self.cube, slot) {
+            return steps;
+        }
+
+        let (front, right) = SLOT_FRAMES[slot - 8];
+        let inserts = [
+            frame(RIGHT_EDGE_INSERT, front, right),
+            frame(LEFT_EDGE_INSERT, front, right),
+        ];
+
+        let moves = (0..4)
+            .flat_map(|u_turns| {
+                inserts.iter().map(move |insert| {
+                    let mut moves = u_turn(u_turns);
+                    moves.extend(insert);
+                    moves
+                })
+            })
+            .find(|moves| {
+                let cube = apply(&self.cube, moves);
+                is_edge_solved(&cube, slot) && (4..8).all(|slot| is_corner_solved(&cube, slot))
+            })
+            .unwrap();
+
+        steps.push(self.apply(
+            format!("Move the {:?} edge next to its slot and insert it", name),
+            moves,
+        ));
+
+        steps
+    }
+
+    fn orient_last_layer(&mut self) -> Vec<Step> {
+        vec![
+            self.repeat_until("Orient the top edges with", EDGE_ORIENTATION, |cube| {
+                cube.edge_orientation[..4].iter().all(|&flip| flip == 0)
+            }),
+            self.repeat_until("Orient the top corners with", CORNER_ORIENTATION, |cube| {
+                cube.corner_orientation[..4].iter().all(|&twist| twist == 0)
+            }),
+        ]
+    }
+
+    fn permute_last_layer(&mut self) -> Vec<Step> {
+        vec![
+            self.repeat_until("Permute the top corners with", CORNER_PERMUTATION, |cube| {
+                (0..4).all(|slot| cube.corner_permutation[slot] == slot as u8)
+            }),
+            self.repeat_until("Permute the top edges with", EDGE_PERMUTATION, |cube| {
+                *cube == CubieCube::SOLVED
+            }),
+        ]
+    }
+
+    /// Applies the algorithm as few times as possible, turning the top layer in between, until
+    /// the cube reaches the goal.
+    fn repeat_until(
+        &mut self,
+        description: &str,
+        algorithm: &str,
+        goal: impl Fn(&CubieCube) -> bool,
+    ) -> Step {
+        let algorithm_moves = parse(algorithm);
+        let steps = [u_turn(1), u_turn(2), u_turn(3), algorithm_moves];
+
+        // breadth first, so that the fewest steps are used
+        let mut parents: HashMap<CubieCube, (CubieCube, usize)> = HashMap::new();
+        let mut queue = VecDeque::from([self.cube]);
+        let mut end = None;
+
+        while let Some(cube) = queue.pop_front() {
+            if goal(&cube) {
+                end = Some(cube);
+                break;
+            }
+
+            for (i, step) in steps.iter().enumerate() {
+                let next = apply(&cube, step);
+
+                if next != self.cube && !parents.contains_key(&next) {
+                    parents.insert(next, (cube, i));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let mut cube = end.expect("the algorithm and top layer turns reach the goal");
+        let mut path = Vec::new();
+
+        while let Some(&(parent, step)) = parents.get(&cube) {
+            path.push(step);
+            cube = parent;
+        }
+
+        let moves = path
+            .into_iter()
+            .rev()
+            .flat_map(|step| steps[step].iter().copied())
+            .collect();
+
+        self.apply(format!("{} {}", description, algorithm), moves)
+    }
+
+    fn corner_slot(&self, corner: usize) -> usize {
+        self.cube
+            .corner_permutation
+            .iter()
+            .position(|&piece| piece as usize == corner)
+            .unwrap()
+    }
+
+    fn edge_slot(&self, edge: usize) -> usize {
+        self.cube
+            .edge_permutation
+            .iter()
+            .position(|&piece| piece as usize == edge)
+            .unwrap()
+    }
+}
+
+fn is_corner_solved(cube: &CubieCube, slot: usize) -> bool {
+    cube.corner_permutation[slot] == slot as u8 && cube.corner_orientation[slot] == 0
+}
+
+fn is_edge_solved(cube: &CubieCube, slot: usize) -> bool {
+    cube.edge_permutation[slot] == slot as u8 && cube.edge_orientation[slot] == 0
+}
+
+fn parse(algorithm: &str) -> Vec<Move> {
+    algorithm
+        .parse::<Algorithm>()
+        .unwrap()
+        .moves()
+        .into_iter()
+        .map(|(m, _)| m)
+        .collect()
+}
+
+/// Parses an algorithm written for the front right slot and turns it into one for the slot with
+/// the given front and right face.
+fn frame(algorithm: &str, front: Face, right: Face) -> Vec<Move> {
+    parse(algorithm)
+        .into_iter()
+        .map(|m| {
+            let MoveKind::Face(face, layers) = m.kind else {
+                return m;
+            };
+
+            let face = match face {
+                Face::Front => front,
+                Face::Back => front.opposite(),
+                Face::Right => right,
+                Face::Left => right.opposite(),
+                face => face,
+            };
+
+            Move {
+                kind: MoveKind::Face(face, layers),
+                amount: m.amount,
+            }
+        })
+        .collect()
+}
+
+fn format_moves(moves: &[Move]) -> String {
+    moves
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn face_move(face: Face, quarter_turns: i32) -> Move {
+    let amount = match quarter_turns.rem_euclid(4) {
+        3 => -1,
+        quarter_turns => quarter_turns,
+    };
+
+    Move {
+        kind: MoveKind::Face(face, Layers::Single(1)),
+        amount,
+    }
+}
+
+fn u_turn(quarter_turns: i32) -> Vec<Move> {
+    if quarter_turns.rem_euclid(4) == 0 {
+        Vec::new()
+    } else {
+        vec![face_move(Face::Top, quarter_turns)]
+    }
+}
+
+fn apply(cube: &CubieCube, moves: &[Move]) -> CubieCube {
+    moves.iter().fold(*cube, |cube, m| match m.kind {
+        MoveKind::Face(face, Layers::Single(1)) => cube.turn(face, m.amount),
+        _ => unreachable!("the algorithms only turn outer layers"),
+    })
+}
+
+/// Where each turn of [`turns`] moves a single edge, whose slot and flip are stored as
+/// `slot * 2 + flip`.
+fn edge_turns() -> [[usize; 24]; 18] {
+    turns().map(|(face, quarter_turns)| {
+        let turn = CubieCube::SOLVED.turn(face, quarter_turns);
+        let mut placements = [0; 24];
+
+        for (placement, next_placement) in placements.iter_mut().enumerate() {
+            let (slot, flip) = (placement / 2, placement % 2);
+            let next = turn
+                .edge_permutation
+                .iter()
+                .position(|&from| from as usize == slot)
+                .unwrap();
+
+            *next_placement = next * 2 + (flip + turn.edge_orientation[next] as usize) % 2;
+        }
+
+        placements
+    })
+}
+
+fn turns() -> [(Face, i32); 18] {
+    let mut turns = [(Face::Right, 0); 18];
+
+    for (i, turn) in turns.iter_mut().enumerate() {
+        *turn = (Face::ALL[i / 3], i as i32 % 3 + 1);
+    }
+
+    turns
+}
+
+/// Slot and flip of each bottom edge, read as a number in base 24.
+fn cross_index(placements: [usize; 4]) -> usize {
+    placements
+        .into_iter()
+        .fold(0, |index, placement| index * 24 + placement)
+}
+
+fn cross_placements(cube: &CubieCube) -> [usize; 4] {
+    [4, 5, 6, 7].map(|edge| {
+        let slot = cube
+            .edge_permutation
+            .iter()
+            .position(|&piece| piece as usize == edge)
+            .unwrap();
+
+        slot * 2 + cube.edge_orientation[slot] as usize
+    })
+}
+
+/// Distances to the solved cross of all placements of the bottom edges.
+fn cross_distances(edge_turns: &[[usize; 24]; 18]) -> Vec<u8> {
+    let solved = cross_placements(&CubieCube::SOLVED);
+
+    let mut distances = vec![u8::MAX; 24usize.pow(4)];
+    let mut queue = VecDeque::from([solved]);
+    distances[cross_index(solved)] = 0;
+
+    while let Some(placements) = queue.pop_front() {
+        let distance = distances[cross_index(placements)];
+
+        for turn in edge_turns {
+            let next = placements.map(|placement| turn[placement]);
+            let index = cross_index(next);
+
+            if distances[index] == u8::MAX {
+                distances[index] = distance + 1;
+                queue.push_back(next);
+            }
+        }
+    }
+
+    distances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scramble::Scramble;
+
+    /// Whether the pieces each stage is about are solved once the stages up to it are applied.
+    fn is_stage_solved(cube: &CubieCube, kind: StageKind) -> bool {
+        let bottom_edges = (4..8).all(|slot| is_edge_solved(cube, slot));
+        let bottom_corners = (4..8).all(|slot| is_corner_solved(cube, slot));
+        let middle_edges = (8..12).all(|slot| is_edge_solved(cube, slot));
+        let oriented = cube.corner_orientation[..4].iter().all(|&twist| twist == 0)
+            && cube.edge_orientation[..4].iter().all(|&flip| flip == 0);
+
+        match kind {
+            StageKind::Cross => bottom_edges,
+            StageKind::FirstLayerCorners => bottom_edges && bottom_corners,
+            StageKind::SecondLayer => bottom_edges && bottom_corners && middle_edges,
+            StageKind::Oll => bottom_edges && bottom_corners && middle_edges && oriented,
+            StageKind::Pll => *cube == CubieCube::SOLVED,
+        }
+    }
+
+    #[test]
+    fn stages_solve_scrambled_cubes() {
+        for seed in 0..10 {
+            let scramble = Scramble::rubik(3, seed);
+            let mut state = RubikState::new(3);
+
+            for m in &scramble.moves {
+                state.turn(&m.to_turn(3).unwrap());
+            }
+
+            let stages = solve_state(&state).unwrap();
+            let mut cube = to_cubie_cube(&state).unwrap();
+
+            for stage in &stages {
+                for step in &stage.steps {
+                    cube = apply(&cube, &step.moves);
+                }
+
+                assert!(
+                    is_stage_solved(&cube, stage.kind),
+                    "{} isn't solved for the scramble {}",
+                    stage.kind,
+                    scramble
+                );
+            }
+
+            let kinds = stages.iter().map(|stage| stage.kind).collect::<Vec<_>>();
+            assert_eq!(
+                kinds,
+                [
+                    StageKind::Cross,
+                    StageKind::FirstLayerCorners,
+                    StageKind::SecondLayer,
+                    StageKind::Oll,
+                    StageKind::Pll,
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn moves_of_a_stage_do_the_same_as_its_steps() {
+        let mut state = RubikState::new(3);
+
+        for m in &Scramble::rubik(3, 7).moves {
+            state.turn(&m.to_turn(3).unwrap());
+        }
+
+        let cube = to_cubie_cube(&state).unwrap();
+        let stages = solve_state(&state).unwrap();
+        let moves = stages.iter().flat_map(Stage::moves).collect::<Vec<_>>();
+
+        assert_eq!(apply(&cube, &moves), CubieCube::SOLVED);
+    }
+
+    #[test]
+    fn solved_cube_needs_no_moves() {
+        let stages = solve(&CubieCube::SOLVED).unwrap();
+        assert!(stages.iter().all(|stage| stage.moves().is_empty()));
+    }
+
+    #[test]
+    fn unsolvable_cube_is_rejected() {
+        let mut cube = CubieCube::SOLVED;
+        cube.edge_orientation[0] = 1;
+
+        assert_eq!(solve(&cube), Err(SolveError::Unsolvable));
+    }
+}
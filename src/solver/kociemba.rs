@@ -0,0 +1,718 @@
+use crate::notation::{Layers, Move, MoveKind};
+use crate::puzzle::rubiks::cubie::{permutation_index, CubieCube};
+use crate::puzzle::rubiks::state::{Face, RubikState};
+use crate::solver::{to_cubie_cube, SolveError};
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Length up to which solutions are searched by default.
+pub const DEFAULT_MAX_LENGTH: usize = 22;
+
+/// Name of the file in the cache directory of the user, in which [`Solver::shared`] caches its
+/// tables.
+pub const CACHE_FILE_NAME: &str = "rubiks-cube-rs-kociemba.tables";
+
+/// Environment variable with the directory [`Solver::shared`] caches its tables in instead, e.g.
+/// to keep CI runs from writing to the cache of the user.
+pub const CACHE_DIR_VARIABLE: &str = "RUBIKS_CUBE_CACHE_DIR";
+
+const CACHE_MAGIC: &[u8; 8] = b"KOCIEMBA";
+const CACHE_VERSION: u32 = 2;
+
+/// Longest phase 2 solution that is tried after each phase 1 solution, which keeps the search
+/// from spending too much time on phase 1 solutions that leave the cube in a bad state.
+const MAX_PHASE_2_LENGTH: usize = 12;
+
+/// Clockwise quarter, half and counterclockwise quarter turn of each face in [`Face::ALL`].
+const NUMBER_OF_MOVES: usize = 18;
+
+/// Moves which keep the cube in the phase 2 subgroup: `U`, `D`, `R2`, `L2`, `F2` and `B2`.
+const PHASE_2_MOVES: [usize; 10] = [6, 7, 8, 9, 10, 11, 1, 4, 13, 16];
+
+const TWISTS: usize = 2187;
+const FLIPS: usize = 2048;
+const SLICES: usize = 495;
+const CORNER_PERMUTATIONS: usize = 40_320;
+const EDGE_PERMUTATIONS: usize = 40_320;
+const SLICE_PERMUTATIONS: usize = 24;
+
+/// Move and pruning tables, which take a moment to generate and can therefore be cached.
+pub struct Tables {
+    twist_moves: Vec<u16>,
+    flip_moves: Vec<u16>,
+    slice_moves: Vec<u16>,
+    corner_permutation_moves: Vec<u16>,
+    edge_permutation_moves: Vec<u16>,
+    slice_permutation_moves: Vec<u16>,
+    twist_slice_distances: Vec<u8>,
+    flip_slice_distances: Vec<u8>,
+    corner_slice_distances: Vec<u8>,
+    edge_slice_distances: Vec<u8>,
+}
+
+impl Tables {
+    pub fn generate() -> Self {
+        let all_moves = (0..NUMBER_OF_MOVES).map(move_cube).collect::<Vec<_>>();
+        let phase_2_moves = PHASE_2_MOVES.map(move_cube);
+
+        let twist_moves = move_table(TWISTS, &all_moves, twist);
+        let flip_moves = move_table(FLIPS, &all_moves, flip);
+        let slice_moves = move_table(SLICES, &all_moves, slice);
+
+        let corner_permutation_moves =
+            move_table(CORNER_PERMUTATIONS, &phase_2_moves, corner_permutation);
+        let edge_permutation_moves =
+            move_table(EDGE_PERMUTATIONS, &phase_2_moves, edge_permutation);
+        let slice_permutation_moves =
+            move_table(SLICE_PERMUTATIONS, &phase_2_moves, slice_permutation);
+
+        Self {
+            twist_slice_distances: pruning_table(&twist_moves, &slice_moves, SLICES),
+            flip_slice_distances: pruning_table(&flip_moves, &slice_moves, SLICES),
+            corner_slice_distances: pruning_table(
+                &corner_permutation_moves,
+                &slice_permutation_moves,
+                SLICE_PERMUTATIONS,
+            ),
+            edge_slice_distances: pruning_table(
+                &edge_permutation_moves,
+                &slice_permutation_moves,
+                SLICE_PERMUTATIONS,
+            ),
+            twist_moves,
+            flip_moves,
+            slice_moves,
+            corner_permutation_moves,
+            edge_permutation_moves,
+            slice_permutation_moves,
+        }
+    }
+
+    /// Reads the tables from the cache file or generates them and tries to write them to it,
+    /// which replaces a cache file that is damaged or of another version.
+    pub fn load_or_generate(path: &Path) -> Self {
+        if let Some(tables) = std::fs::File::open(path)
+            .ok()
+            .and_then(|file| Self::read(std::io::BufReader::new(file)).ok())
+        {
+            return tables;
+        }
+
+        let tables = Self::generate();
+
+        // the cache is only an optimization, so failing to write it is fine
+        let _ = tables.write_file(path);
+
+        tables
+    }
+
+    /// Reads tables written by [`Tables::write`], which fails unless the checksum matches and
+    /// every entry is in range, so that a damaged file can't send the search out of bounds.
+    pub fn read(mut reader: impl Read) -> std::io::Result<Self> {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+
+        if &magic != CACHE_MAGIC || read_u32(&mut reader)? != CACHE_VERSION {
+            return Err(invalid_data("not a table cache of this version"));
+        }
+
+        let checksum = read_u64(&mut reader)?;
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+
+        if fnv_1a(&data) != checksum {
+            return Err(invalid_data(
+                "the checksum of the table cache doesn't match",
+            ));
+        }
+
+        let mut data = data.as_slice();
+
+        let tables = Self {
+            twist_moves: read_u16s(&mut data, TWISTS * NUMBER_OF_MOVES)?,
+            flip_moves: read_u16s(&mut data, FLIPS * NUMBER_OF_MOVES)?,
+            slice_moves: read_u16s(&mut data, SLICES * NUMBER_OF_MOVES)?,
+            corner_permutation_moves: read_u16s(
+                &mut data,
+                CORNER_PERMUTATIONS * PHASE_2_MOVES.len(),
+            )?,
+            edge_permutation_moves: read_u16s(&mut data, EDGE_PERMUTATIONS * PHASE_2_MOVES.len())?,
+            slice_permutation_moves: read_u16s(
+                &mut data,
+                SLICE_PERMUTATIONS * PHASE_2_MOVES.len(),
+            )?,
+            twist_slice_distances: read_u8s(&mut data, TWISTS * SLICES)?,
+            flip_slice_distances: read_u8s(&mut data, FLIPS * SLICES)?,
+            corner_slice_distances: read_u8s(&mut data, CORNER_PERMUTATIONS * SLICE_PERMUTATIONS)?,
+            edge_slice_distances: read_u8s(&mut data, EDGE_PERMUTATIONS * SLICE_PERMUTATIONS)?,
+        };
+
+        if !data.is_empty() || !tables.is_valid() {
+            return Err(invalid_data("the table cache contains invalid entries"));
+        }
+
+        Ok(tables)
+    }
+
+    /// Writes the tables together with a checksum of them.
+    pub fn write(&self, mut writer: impl Write) -> std::io::Result<()> {
+        let mut data = Vec::new();
+
+        for table in [
+            &self.twist_moves,
+            &self.flip_moves,
+            &self.slice_moves,
+            &self.corner_permutation_moves,
+            &self.edge_permutation_moves,
+            &self.slice_permutation_moves,
+        ] {
+            data.extend(table.iter().flat_map(|value| value.to_le_bytes()));
+        }
+
+        for table in [
+            &self.twist_slice_distances,
+            &self.flip_slice_distances,
+            &self.corner_slice_distances,
+            &self.edge_slice_distances,
+        ] {
+            data.extend_from_slice(table);
+        }
+
+        writer.write_all(CACHE_MAGIC)?;
+        writer.write_all(&CACHE_VERSION.to_le_bytes())?;
+        writer.write_all(&fnv_1a(&data).to_le_bytes())?;
+        writer.write_all(&data)?;
+        writer.flush()
+    }
+
+    /// Writes the tables to a file next to the path and moves it there once it is complete, so
+    /// that programs reading the cache at the same time never see half of it.
+    fn write_file(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+
+        let partial = path.with_extension(format!("{}.partial", std::process::id()));
+
+        let written = std::fs::File::create(&partial)
+            .and_then(|file| self.write(std::io::BufWriter::new(file)))
+            .and_then(|()| std::fs::rename(&partial, path));
+
+        if written.is_err() {
+            let _ = std::fs::remove_file(&partial);
+        }
+
+        written
+    }
+
+    /// Whether every move leads to an existing coordinate and only the solved coordinates are
+    /// at distance 0, which the search relies on.
+    fn is_valid(&self) -> bool {
+        let move_tables = [
+            (&self.twist_moves, TWISTS),
+            (&self.flip_moves, FLIPS),
+            (&self.slice_moves, SLICES),
+            (&self.corner_permutation_moves, CORNER_PERMUTATIONS),
+            (&self.edge_permutation_moves, EDGE_PERMUTATIONS),
+            (&self.slice_permutation_moves, SLICE_PERMUTATIONS),
+        ];
+
+        let pruning_tables = [
+            &self.twist_slice_distances,
+            &self.flip_slice_distances,
+            &self.corner_slice_distances,
+            &self.edge_slice_distances,
+        ];
+
+        move_tables.iter().all(|(table, size)| {
+            table
+                .iter()
+                .all(|&coordinate| (coordinate as usize) < *size)
+        }) && pruning_tables.iter().all(|table| {
+            table
+                .iter()
+                .enumerate()
+                .all(|(index, &distance)| (distance == 0) == (index == 0))
+        })
+    }
+}
+
+fn invalid_data(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+fn read_u32(reader: &mut impl Read) -> std::io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut impl Read) -> std::io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_u16s(reader: &mut impl Read, length: usize) -> std::io::Result<Vec<u16>> {
+    let bytes = read_u8s(reader, length * 2)?;
+
+    Ok(bytes
+        .chunks_exact(2)
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+        .collect())
+}
+
+fn read_u8s(reader: &mut impl Read, length: usize) -> std::io::Result<Vec<u8>> {
+    let mut bytes = vec![0; length];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// 64-bit FNV-1a hash, which is enough to notice damaged files without pulling in a crate.
+fn fnv_1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Directory for files of the current user which can be recreated at any time, e.g.
+/// `~/.cache` on Linux, or `None` if the environment doesn't say where it is. Tests use the
+/// temporary directory instead, unless [`CACHE_DIR_VARIABLE`] says otherwise.
+fn cache_dir() -> Option<PathBuf> {
+    let variable = |name: &str| {
+        std::env::var_os(name)
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+    };
+
+    if let Some(directory) = variable(CACHE_DIR_VARIABLE) {
+        Some(directory)
+    } else if cfg!(test) {
+        Some(std::env::temp_dir())
+    } else if cfg!(target_os = "windows") {
+        variable("LOCALAPPDATA")
+    } else if cfg!(target_os = "macos") {
+        variable("HOME").map(|home| home.join("Library").join("Caches"))
+    } else {
+        variable("XDG_CACHE_HOME").or_else(|| variable("HOME").map(|home| home.join(".cache")))
+    }
+}
+
+/// Herbert Kociemba's two-phase algorithm.
+///
+/// Phase 1 brings the cube into the subgroup generated by `U, D, R2, L2, F2, B2`, where all
+/// pieces are oriented and the middle layer edges are in the middle layer. Phase 2 solves the
+/// cube with only these moves. Both phases are searched with IDA*, using pruning tables of pairs
+/// of coordinates that describe the parts of the cube relevant to each phase.
+pub struct Solver {
+    tables: Tables,
+}
+
+impl Solver {
+    pub fn new(tables: Tables) -> Self {
+        Self { tables }
+    }
+
+    /// Solver for the whole program, whose tables are generated on first use and cached in the
+    /// cache directory of the user where there is one, see [`CACHE_DIR_VARIABLE`].
+    pub fn shared() -> &'static Solver {
+        static SHARED: OnceLock<Solver> = OnceLock::new();
+
+        SHARED.get_or_init(|| {
+            let tables = match cache_dir() {
+                Some(directory) if !cfg!(target_arch = "wasm32") => {
+                    Tables::load_or_generate(&directory.join(CACHE_FILE_NAME))
+                }
+                _ => Tables::generate(),
+            };
+
+            Solver::new(tables)
+        })
+    }
+
+    pub fn solve_state(
+        &self,
+        state: &RubikState,
+        max_length: usize,
+    ) -> Result<Vec<Move>, SolveError> {
+        self.solve(&to_cubie_cube(state)?, max_length)
+    }
+
+    /// Finds a solution with at most `max_length` outer layer turns, which is not necessarily
+    /// the shortest one.
+    pub fn solve(&self, cube: &CubieCube, max_length: usize) -> Result<Vec<Move>, SolveError> {
+        if !cube.is_solvable() {
+            return Err(SolveError::Unsolvable);
+        }
+
+        let mut search = Search {
+            tables: &self.tables,
+            cube: *cube,
+            max_length,
+            path: Vec::with_capacity(max_length),
+        };
+
+        let (twist, flip, slice) = (twist(cube), flip(cube), slice(cube));
+
+        for depth in 0..=max_length {
+            if search.phase_1(twist, flip, slice, depth) {
+                return Ok(search.path.into_iter().map(to_move).collect());
+            }
+        }
+
+        Err(SolveError::NotFound)
+    }
+}
+
+struct Search<'a> {
+    tables: &'a Tables,
+    cube: CubieCube,
+    max_length: usize,
+    /// Indices of the moves found so far.
+    path: Vec<usize>,
+}
+
+impl<'a> Search<'a> {
+    /// Looks for phase 1 solutions with exactly `depth` more moves and tries to finish each one
+    /// with phase 2.
+    fn phase_1(&mut self, twist: usize, flip: usize, slice: usize, depth: usize) -> bool {
+        let tables = self.tables;
+        let distance = tables.twist_slice_distances[twist * SLICES + slice]
+            .max(tables.flip_slice_distances[flip * SLICES + slice])
+            as usize;
+
+        if depth == 0 {
+            // ending with a phase 2 move means that a shorter phase 1 solution was tried already
+            return distance == 0
+                && self
+                    .path
+                    .last()
+                    .is_none_or(|last| !PHASE_2_MOVES.contains(last))
+                && self.start_phase_2();
+        }
+
+        if distance > depth {
+            return false;
+        }
+
+        for m in 0..NUMBER_OF_MOVES {
+            if self.path.last().is_some_and(|&last| is_redundant(last, m)) {
+                continue;
+            }
+
+            self.path.push(m);
+
+            if self.phase_1(
+                tables.twist_moves[twist * NUMBER_OF_MOVES + m] as usize,
+                tables.flip_moves[flip * NUMBER_OF_MOVES + m] as usize,
+                tables.slice_moves[slice * NUMBER_OF_MOVES + m] as usize,
+                depth - 1,
+            ) {
+                return true;
+            }
+
+            self.path.pop();
+        }
+
+        false
+    }
+
+    fn start_phase_2(&mut self) -> bool {
+        let cube = self
+            .path
+            .iter()
+            .fold(self.cube, |cube, &m| cube.then(&move_cube(m)));
+
+        let (corners, edges, slice) = (
+            corner_permutation(&cube),
+            edge_permutation(&cube),
+            slice_permutation(&cube),
+        );
+
+        let max_depth = (self.max_length - self.path.len()).min(MAX_PHASE_2_LENGTH);
+
+        (0..=max_depth).any(|depth| self.phase_2(corners, edges, slice, depth))
+    }
+
+    fn phase_2(&mut self, corners: usize, edges: usize, slice: usize, depth: usize) -> bool {
+        let tables = self.tables;
+        let distance = tables.corner_slice_distances[corners * SLICE_PERMUTATIONS + slice]
+            .max(tables.edge_slice_distances[edges * SLICE_PERMUTATIONS + slice])
+            as usize;
+
+        if distance == 0 {
+            return true;
+        }
+
+        if distance > depth {
+            return false;
+        }
+
+        for (i, &m) in PHASE_2_MOVES.iter().enumerate() {
+            if self.path.last().is_some_and(|&last| is_redundant(last, m)) {
+                continue;
+            }
+
+            self.path.push(m);
+
+            let index = |coordinate: usize| coordinate * PHASE_2_MOVES.len() + i;
+
+            if self.phase_2(
+                tables.corner_permutation_moves[index(corners)] as usize,
+                tables.edge_permutation_moves[index(edges)] as usize,
+                tables.slice_permutation_moves[index(slice)] as usize,
+                depth - 1,
+            ) {
+                return true;
+            }
+
+            self.path.pop();
+        }
+
+        false
+    }
+}
+
+fn move_face(m: usize) -> Face {
+    Face::ALL[m / 3]
+}
+
+fn move_cube(m: usize) -> CubieCube {
+    CubieCube::SOLVED.turn(move_face(m), m as i32 % 3 + 1)
+}
+
+fn to_move(m: usize) -> Move {
+    Move {
+        kind: MoveKind::Face(move_face(m), Layers::Single(1)),
+        amount: [1, 2, -1][m % 3],
+    }
+}
+
+/// Whether the move turns the same face as the last one, or the opposite face which commutes
+/// with it and is therefore only allowed in one order.
+fn is_redundant(last: usize, m: usize) -> bool {
+    let (last, face) = (last / 3, m / 3);
+    last == face || (last / 2 == face / 2 && last > face)
+}
+
+/// Twists of all but the last corner read as a number in base three.
+fn twist(cube: &CubieCube) -> usize {
+    cube.corner_orientation[..7]
+        .iter()
+        .fold(0, |index, &twist| index * 3 + twist as usize)
+}
+
+/// Flips of all but the last edge read as a number in base two.
+fn flip(cube: &CubieCube) -> usize {
+    cube.edge_orientation[..11]
+        .iter()
+        .fold(0, |index, &flip| index * 2 + flip as usize)
+}
+
+/// Which four slots hold the middle layer edges, regardless of their order.
+fn slice(cube: &CubieCube) -> usize {
+    let mut index = 0;
+    let mut found = 0;
+
+    for slot in (0..12).rev() {
+        if cube.edge_permutation[slot] >= 8 {
+            found += 1;
+            index += binomial(11 - slot, found);
+        }
+    }
+
+    index
+}
+
+fn corner_permutation(cube: &CubieCube) -> usize {
+    permutation_index(&cube.corner_permutation)
+}
+
+/// Permutation of the top and bottom layer edges, only defined within phase 2.
+fn edge_permutation(cube: &CubieCube) -> usize {
+    permutation_index(&cube.edge_permutation[..8])
+}
+
+/// Permutation of the middle layer edges, only defined within phase 2.
+fn slice_permutation(cube: &CubieCube) -> usize {
+    permutation_index(&cube.edge_permutation[8..])
+}
+
+fn binomial(n: usize, k: usize) -> usize {
+    if k > n {
+        return 0;
+    }
+
+    (0..k).fold(1, |result, i| result * (n - i) / (i + 1))
+}
+
+/// Table of the coordinate after each move, found by exploring all coordinates reachable with
+/// the moves from the solved state.
+fn move_table(size: usize, moves: &[CubieCube], coordinate: fn(&CubieCube) -> usize) -> Vec<u16> {
+    let mut table = vec![u16::MAX; size * moves.len()];
+    let mut visited = vec![false; size];
+    let mut queue = VecDeque::from([CubieCube::SOLVED]);
+    visited[coordinate(&CubieCube::SOLVED)] = true;
+
+    while let Some(cube) = queue.pop_front() {
+        let index = coordinate(&cube);
+
+        for (i, m) in moves.iter().enumerate() {
+            let next = cube.then(m);
+            let next_index = coordinate(&next);
+            table[index * moves.len() + i] = next_index as u16;
+
+            if !visited[next_index] {
+                visited[next_index] = true;
+                queue.push_back(next);
+            }
+        }
+    }
+
+    table
+}
+
+/// Distances to the solved state of all pairs of two coordinates.
+fn pruning_table(first_moves: &[u16], second_moves: &[u16], second_size: usize) -> Vec<u8> {
+    let number_of_moves = second_moves.len() / second_size;
+    let first_size = first_moves.len() / number_of_moves;
+
+    let mut table = vec![u8::MAX; first_size * second_size];
+    let mut queue = VecDeque::from([0]);
+    table[0] = 0;
+
+    while let Some(index) = queue.pop_front() {
+        let (first, second) = (index / second_size, index % second_size);
+        let distance = table[index];
+
+        for m in 0..number_of_moves {
+            let next = first_moves[first * number_of_moves + m] as usize * second_size
+                + second_moves[second * number_of_moves + m] as usize;
+
+            if table[next] == u8::MAX {
+                table[next] = distance + 1;
+                queue.push_back(next);
+            }
+        }
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scramble::Rng;
+
+    /// Solver shared by the tests, whose tables are generated instead of read from the cache of
+    /// the user.
+    fn solver() -> &'static Solver {
+        static SOLVER: OnceLock<Solver> = OnceLock::new();
+        SOLVER.get_or_init(|| Solver::new(Tables::generate()))
+    }
+
+    fn random_cube(rng: &mut Rng) -> CubieCube {
+        (0..30).fold(CubieCube::SOLVED, |cube, _| {
+            cube.turn(Face::ALL[rng.below(6) as usize], rng.below(3) as i32 + 1)
+        })
+    }
+
+    fn apply(cube: CubieCube, moves: &[Move]) -> CubieCube {
+        moves.iter().fold(cube, |cube, m| match m.kind {
+            MoveKind::Face(face, Layers::Single(1)) => cube.turn(face, m.amount),
+            _ => panic!("{} is no outer layer turn", m),
+        })
+    }
+
+    fn written(tables: &Tables) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        tables.write(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn solutions_solve_random_cubes() {
+        let mut rng = Rng::new(9);
+
+        for _ in 0..20 {
+            let cube = random_cube(&mut rng);
+            let solution = solver().solve(&cube, DEFAULT_MAX_LENGTH).unwrap();
+
+            assert!(solution.len() <= DEFAULT_MAX_LENGTH);
+            assert_eq!(apply(cube, &solution), CubieCube::SOLVED);
+        }
+    }
+
+    #[test]
+    fn solved_cube_needs_no_moves() {
+        assert_eq!(
+            solver().solve(&CubieCube::SOLVED, DEFAULT_MAX_LENGTH),
+            Ok(Vec::new())
+        );
+    }
+
+    #[test]
+    fn twisted_corner_is_unsolvable() {
+        let mut cube = CubieCube::SOLVED;
+        cube.corner_orientation[0] = 1;
+
+        assert_eq!(
+            solver().solve(&cube, DEFAULT_MAX_LENGTH),
+            Err(SolveError::Unsolvable)
+        );
+    }
+
+    #[test]
+    fn cached_tables_are_read_back() {
+        let bytes = written(&solver().tables);
+        let tables = Tables::read(bytes.as_slice()).unwrap();
+
+        assert_eq!(written(&tables), bytes);
+    }
+
+    #[test]
+    fn damaged_caches_are_rejected() {
+        let bytes = written(&solver().tables);
+
+        assert!(Tables::read(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Tables::read(&bytes[..100]).is_err());
+        assert!(Tables::read(&[][..]).is_err());
+
+        let mut flipped = bytes.clone();
+        flipped[1000] ^= 1;
+        assert!(Tables::read(flipped.as_slice()).is_err());
+
+        let mut longer = bytes;
+        longer.push(0);
+        assert!(Tables::read(longer.as_slice()).is_err());
+    }
+
+    #[test]
+    fn caches_with_entries_out_of_range_are_rejected() {
+        let mut tables = Tables::read(written(&solver().tables).as_slice()).unwrap();
+        tables.slice_moves[7] = SLICES as u16;
+
+        // the checksum matches, since it got written together with the bad entry
+        assert!(Tables::read(written(&tables).as_slice()).is_err());
+
+        tables.slice_moves[7] = 0;
+        tables.edge_slice_distances[5] = 0;
+        assert!(Tables::read(written(&tables).as_slice()).is_err());
+    }
+
+    #[test]
+    fn damaged_cache_files_get_replaced() {
+        let path =
+            std::env::temp_dir().join(format!("rubiks-cube-rs-test-{}.tables", std::process::id()));
+        std::fs::write(&path, b"KOCIEMBA garbage").unwrap();
+
+        let tables = Tables::load_or_generate(&path);
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(bytes, written(&tables));
+        assert_eq!(bytes, written(&solver().tables));
+    }
+}
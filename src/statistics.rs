@@ -0,0 +1,282 @@
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub enum Penalty {
+    #[default]
+    None,
+    /// Two seconds get added, e.g. for starting after 15 seconds of inspection.
+    PlusTwo,
+    /// Did not finish, e.g. for starting after 17 seconds of inspection.
+    Dnf,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Solve {
+    /// Time on the timer, without the penalty.
+    pub time: Duration,
+    pub penalty: Penalty,
+}
+
+impl Solve {
+    pub fn result(&self) -> SolveTime {
+        match self.penalty {
+            Penalty::None => SolveTime::Finished(self.time),
+            Penalty::PlusTwo => SolveTime::Finished(self.time + Duration::from_secs(2)),
+            Penalty::Dnf => SolveTime::Dnf,
+        }
+    }
+}
+
+impl Display for Solve {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.penalty {
+            Penalty::None => write!(f, "{}", self.result()),
+            Penalty::PlusTwo => write!(f, "{}+", self.result()),
+            Penalty::Dnf => write!(f, "DNF({})", SolveTime::Finished(self.time)),
+        }
+    }
+}
+
+/// A single result or an average, ordered so that every finished time is better than a DNF.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum SolveTime {
+    Finished(Duration),
+    Dnf,
+}
+
+impl Display for SolveTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveTime::Finished(time) => {
+                let centiseconds = time.as_millis() / 10;
+                let minutes = centiseconds / 6000;
+                let seconds = centiseconds / 100 % 60;
+                let centiseconds = centiseconds % 100;
+
+                if minutes > 0 {
+                    write!(f, "{}:{:02}.{:02}", minutes, seconds, centiseconds)
+                } else {
+                    write!(f, "{}.{:02}", seconds, centiseconds)
+                }
+            }
+            SolveTime::Dnf => write!(f, "DNF"),
+        }
+    }
+}
+
+/// Summary of a session, where the means and averages are the ones of the latest solves and are
+/// `None` while there are not enough solves yet.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub struct Statistics {
+    pub best: Option<SolveTime>,
+    pub worst: Option<SolveTime>,
+    pub mo3: Option<SolveTime>,
+    pub ao5: Option<SolveTime>,
+    pub ao12: Option<SolveTime>,
+    pub ao100: Option<SolveTime>,
+}
+
+impl Statistics {
+    pub fn new(solves: &[Solve]) -> Self {
+        Self {
+            best: best(solves),
+            worst: worst(solves),
+            mo3: latest(solves, 3).map(mean),
+            ao5: latest(solves, 5).map(average),
+            ao12: latest(solves, 12).map(average),
+            ao100: latest(solves, 100).map(average),
+        }
+    }
+}
+
+fn latest(solves: &[Solve], count: usize) -> Option<&[Solve]> {
+    solves
+        .len()
+        .checked_sub(count)
+        .map(|start| &solves[start..])
+}
+
+pub fn best(solves: &[Solve]) -> Option<SolveTime> {
+    solves.iter().map(Solve::result).min()
+}
+
+pub fn worst(solves: &[Solve]) -> Option<SolveTime> {
+    solves.iter().map(Solve::result).max()
+}
+
+/// Mean of all solves, which is a DNF as soon as one of them is.
+pub fn mean(solves: &[Solve]) -> SolveTime {
+    let mut total = Duration::ZERO;
+
+    for solve in solves {
+        match solve.result() {
+            SolveTime::Finished(time) => total += time,
+            SolveTime::Dnf => return SolveTime::Dnf,
+        }
+    }
+
+    SolveTime::Finished(total / solves.len().max(1) as u32)
+}
+
+/// Mean of the solves without the best and worst 5%, rounded up, as the WCA calculates
+/// averages. DNFs count as the worst solves, so the average is only a DNF if they can't all be
+/// left out.
+pub fn average(solves: &[Solve]) -> SolveTime {
+    let trimmed = (solves.len() * 5).div_ceil(100);
+
+    let mut results = solves.iter().map(Solve::result).collect::<Vec<_>>();
+    results.sort();
+
+    if results.len() <= 2 * trimmed {
+        return mean(solves);
+    }
+
+    let counted = &results[trimmed..results.len() - trimmed];
+    let mut total = Duration::ZERO;
+
+    for result in counted {
+        match result {
+            SolveTime::Finished(time) => total += *time,
+            SolveTime::Dnf => return SolveTime::Dnf,
+        }
+    }
+
+    SolveTime::Finished(total / counted.len() as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finished(seconds: f64) -> Solve {
+        Solve {
+            time: Duration::from_secs_f64(seconds),
+            penalty: Penalty::None,
+        }
+    }
+
+    fn dnf(seconds: f64) -> Solve {
+        Solve {
+            penalty: Penalty::Dnf,
+            ..finished(seconds)
+        }
+    }
+
+    fn seconds(seconds: f64) -> SolveTime {
+        SolveTime::Finished(Duration::from_secs_f64(seconds))
+    }
+
+    fn solves(seconds: impl IntoIterator<Item = u64>) -> Vec<Solve> {
+        seconds.into_iter().map(|s| finished(s as f64)).collect()
+    }
+
+    #[test]
+    fn best_and_worst() {
+        let solves = [finished(12.0), finished(9.5), finished(14.25)];
+        assert_eq!(best(&solves), Some(seconds(9.5)));
+        assert_eq!(worst(&solves), Some(seconds(14.25)));
+
+        let with_dnf = [finished(12.0), dnf(8.0)];
+        assert_eq!(best(&with_dnf), Some(seconds(12.0)));
+        assert_eq!(worst(&with_dnf), Some(SolveTime::Dnf));
+
+        assert_eq!(best(&[]), None);
+        assert_eq!(worst(&[]), None);
+    }
+
+    #[test]
+    fn mo3_counts_every_solve() {
+        let statistics = Statistics::new(&solves([20, 10, 11, 12]));
+        assert_eq!(statistics.mo3, Some(seconds(11.0)));
+
+        let statistics = Statistics::new(&[finished(10.0), finished(11.0), dnf(9.0)]);
+        assert_eq!(statistics.mo3, Some(SolveTime::Dnf));
+    }
+
+    #[test]
+    fn ao5_leaves_out_the_best_and_worst_solve() {
+        let statistics = Statistics::new(&solves([30, 10, 12, 14, 2]));
+        assert_eq!(statistics.ao5, Some(seconds(12.0)));
+    }
+
+    #[test]
+    fn ao12_leaves_out_the_best_and_worst_solve() {
+        let statistics = Statistics::new(&solves([1, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 60]));
+        assert_eq!(statistics.ao12, Some(seconds(10.0)));
+    }
+
+    #[test]
+    fn ao100_leaves_out_the_best_and_worst_five_solves() {
+        let statistics = Statistics::new(&solves(1..=100));
+        // the mean of 6 to 95
+        assert_eq!(statistics.ao100, Some(seconds(50.5)));
+    }
+
+    #[test]
+    fn averages_only_count_the_latest_solves() {
+        let statistics = Statistics::new(&solves([100, 100, 10, 10, 10, 10, 10]));
+        assert_eq!(statistics.ao5, Some(seconds(10.0)));
+        assert_eq!(statistics.mo3, Some(seconds(10.0)));
+        assert_eq!(statistics.worst, Some(seconds(100.0)));
+    }
+
+    #[test]
+    fn a_single_dnf_is_left_out_of_an_average() {
+        let solves = [
+            finished(10.0),
+            dnf(5.0),
+            finished(12.0),
+            finished(14.0),
+            finished(9.0),
+        ];
+        assert_eq!(average(&solves), seconds(12.0));
+    }
+
+    #[test]
+    fn two_dnfs_make_the_average_a_dnf() {
+        let solves = [
+            finished(10.0),
+            dnf(5.0),
+            finished(12.0),
+            dnf(14.0),
+            finished(9.0),
+        ];
+        assert_eq!(average(&solves), SolveTime::Dnf);
+    }
+
+    #[test]
+    fn plus_two_adds_two_seconds() {
+        let plus_two = Solve {
+            penalty: Penalty::PlusTwo,
+            ..finished(9.0)
+        };
+
+        assert_eq!(plus_two.result(), seconds(11.0));
+        assert_eq!(plus_two.to_string(), "11.00+");
+
+        let solves = [
+            finished(10.0),
+            plus_two,
+            finished(12.0),
+            finished(20.0),
+            finished(1.0),
+        ];
+        assert_eq!(average(&solves), seconds(11.0));
+        assert_eq!(best(&[finished(10.5), plus_two]), Some(seconds(10.5)));
+    }
+
+    #[test]
+    fn too_few_solves_have_no_means_or_averages() {
+        let statistics = Statistics::new(&solves([10, 11]));
+        assert_eq!(statistics.best, Some(seconds(10.0)));
+        assert_eq!(statistics.mo3, None);
+        assert_eq!(statistics.ao5, None);
+
+        let statistics = Statistics::new(&solves(1..=99));
+        assert!(statistics.ao12.is_some());
+        assert_eq!(statistics.ao100, None);
+
+        assert_eq!(Statistics::new(&[]), Statistics::default());
+    }
+}
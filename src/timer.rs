@@ -0,0 +1,211 @@
+use crate::statistics::{Penalty, Solve, Statistics};
+use crate::turn::SolvedEvent;
+use bevy::prelude::*;
+use std::time::Duration;
+
+/// Key which arms, starts and stops the timer.
+pub const TIMER_KEY: KeyCode = KeyCode::Space;
+
+/// Inspection time after which starting the solve adds two seconds.
+const INSPECTION_TIME: Duration = Duration::from_secs(15);
+
+/// Inspection time after which the solve counts as not finished.
+const INSPECTION_LIMIT: Duration = Duration::from_secs(17);
+
+pub struct TimerPlugin;
+
+impl Plugin for TimerPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(TimerSettings::default())
+            .init_resource::<TimerState>()
+            .init_resource::<Session>()
+            .add_system(update_timer);
+    }
+}
+
+#[derive(Debug, Copy, Clone, Resource)]
+pub struct TimerSettings {
+    /// Whether the first press starts the WCA inspection before the solve.
+    pub inspection: bool,
+    /// How long the timer key has to be held before releasing it starts the solve.
+    pub hold_duration: Duration,
+}
+
+impl Default for TimerSettings {
+    fn default() -> Self {
+        Self {
+            inspection: true,
+            hold_duration: Duration::from_millis(300),
+        }
+    }
+}
+
+/// All timestamps are taken from [`Time::elapsed`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default, Resource)]
+pub enum TimerState {
+    #[default]
+    Idle,
+    Inspecting {
+        start: Duration,
+    },
+    /// The timer key is held, but not yet long enough to start the solve when it is released.
+    Holding {
+        inspection_start: Option<Duration>,
+        hold_start: Duration,
+    },
+    /// Releasing the timer key starts the solve.
+    Armed {
+        inspection_start: Option<Duration>,
+    },
+    Running {
+        start: Duration,
+        penalty: Penalty,
+    },
+    /// Waits for the timer key to be released, so that stopping does not arm it again.
+    Stopped,
+}
+
+impl TimerState {
+    /// Time to show on the timer, which counts the inspection or the solve.
+    pub fn display_time(&self, now: Duration) -> Duration {
+        match *self {
+            TimerState::Inspecting { start }
+            | TimerState::Holding {
+                inspection_start: Some(start),
+                ..
+            }
+            | TimerState::Armed {
+                inspection_start: Some(start),
+            }
+            | TimerState::Running { start, .. } => now.saturating_sub(start),
+            _ => Duration::ZERO,
+        }
+    }
+
+    fn inspection_start(&self) -> Option<Duration> {
+        match *self {
+            TimerState::Inspecting { start } => Some(start),
+            TimerState::Holding {
+                inspection_start, ..
+            }
+            | TimerState::Armed { inspection_start } => inspection_start,
+            _ => None,
+        }
+    }
+}
+
+/// All solves since the app started.
+#[derive(Debug, Default, Resource)]
+pub struct Session {
+    pub solves: Vec<Solve>,
+}
+
+impl Session {
+    pub fn statistics(&self) -> Statistics {
+        Statistics::new(&self.solves)
+    }
+
+    fn add(&mut self, solve: Solve) {
+        self.solves.push(solve);
+
+        let statistics = self.statistics();
+        let format = |time: Option<_>| time.map_or("-".to_string(), |time| time.to_string());
+
+        info!(
+            "solve {}: {} (mo3 {}, ao5 {}, ao12 {}, ao100 {}, best {}, worst {})",
+            self.solves.len(),
+            solve,
+            format(statistics.mo3),
+            format(statistics.ao5),
+            format(statistics.ao12),
+            format(statistics.ao100),
+            format(statistics.best),
+            format(statistics.worst),
+        );
+    }
+}
+
+fn get_inspection_penalty(inspection: Duration) -> Penalty {
+    if inspection > INSPECTION_LIMIT {
+        Penalty::Dnf
+    } else if inspection > INSPECTION_TIME {
+        Penalty::PlusTwo
+    } else {
+        Penalty::None
+    }
+}
+
+fn update_timer(
+    mut timer_state: ResMut<TimerState>,
+    mut session: ResMut<Session>,
+    mut solved_events: EventReader<SolvedEvent>,
+    timer_settings: Res<TimerSettings>,
+    keyboard_input: Res<Input<KeyCode>>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed();
+    let is_solved = solved_events.iter().count() > 0;
+
+    if let Some(inspection_start) = timer_state.inspection_start() {
+        if get_inspection_penalty(now - inspection_start) == Penalty::Dnf {
+            session.add(Solve {
+                time: Duration::ZERO,
+                penalty: Penalty::Dnf,
+            });
+            *timer_state = TimerState::Stopped;
+            return;
+        }
+    }
+
+    *timer_state = match *timer_state {
+        TimerState::Idle if keyboard_input.just_pressed(TIMER_KEY) => {
+            if timer_settings.inspection {
+                TimerState::Inspecting { start: now }
+            } else {
+                TimerState::Holding {
+                    inspection_start: None,
+                    hold_start: now,
+                }
+            }
+        }
+        TimerState::Inspecting { start } if keyboard_input.just_pressed(TIMER_KEY) => {
+            TimerState::Holding {
+                inspection_start: Some(start),
+                hold_start: now,
+            }
+        }
+        TimerState::Holding {
+            inspection_start,
+            hold_start,
+        } => {
+            if !keyboard_input.pressed(TIMER_KEY) {
+                match inspection_start {
+                    Some(start) => TimerState::Inspecting { start },
+                    None => TimerState::Idle,
+                }
+            } else if now - hold_start >= timer_settings.hold_duration {
+                TimerState::Armed { inspection_start }
+            } else {
+                return;
+            }
+        }
+        TimerState::Armed { inspection_start } if keyboard_input.just_released(TIMER_KEY) => {
+            TimerState::Running {
+                start: now,
+                penalty: inspection_start
+                    .map_or(Penalty::None, |start| get_inspection_penalty(now - start)),
+            }
+        }
+        TimerState::Running { start, penalty }
+            if is_solved || keyboard_input.just_pressed(TIMER_KEY) =>
+        {
+            session.add(Solve {
+                time: now - start,
+                penalty,
+            });
+            TimerState::Stopped
+        }
+        TimerState::Stopped if !keyboard_input.pressed(TIMER_KEY) => TimerState::Idle,
+        _ => return,
+    };
+}
@@ -0,0 +1,310 @@
+use crate::puzzle::{CurrentPuzzle, PuzzleRender, PuzzleTurn};
+use crate::selection::SpawnedPuzzle;
+use crate::view::View;
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+pub struct TurnPlugin;
+
+impl Plugin for TurnPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(TurnSettings::default())
+            .init_resource::<TurnQueue>()
+            .init_resource::<PieceEntities>()
+            .add_event::<TurnEvent>()
+            .add_event::<SolvedEvent>()
+            .add_system(queue_turns.label("queue_turns"))
+            .add_system(animate_turns.after("queue_turns"));
+    }
+}
+
+/// Marks a spawned mesh as the piece with the given index of the puzzle.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Component)]
+pub struct Piece(pub usize);
+
+/// The spawned entity of every piece of the puzzle, indexed like [`Piece`].
+#[derive(Debug, Default, Resource)]
+pub struct PieceEntities(pub Vec<Entity>);
+
+/// Requests a turn, which gets applied and animated after all previously requested turns.
+#[derive(Debug, Clone)]
+pub struct TurnEvent(pub PuzzleTurn);
+
+/// Sent when a turn solves the puzzle, as soon as it is applied and before it is animated.
+#[derive(Debug, Copy, Clone)]
+pub struct SolvedEvent;
+
+#[derive(Debug, Copy, Clone, Resource)]
+pub struct TurnSettings {
+    /// Seconds a single step takes, like a quarter turn of a cube. Every further step of the same
+    /// turn takes half as long again, so half turns take one and a half times as long.
+    pub duration: f32,
+}
+
+impl Default for TurnSettings {
+    fn default() -> Self {
+        Self { duration: 0.2 }
+    }
+}
+
+#[derive(Debug, Default, Resource)]
+pub struct TurnQueue {
+    pending: VecDeque<PuzzleTurn>,
+    active: Option<ActiveTurn>,
+    /// Whether turns are applied right away instead of being animated.
+    skipping: bool,
+}
+
+impl TurnQueue {
+    pub fn is_idle(&self) -> bool {
+        self.active.is_none() && self.pending.is_empty()
+    }
+
+    /// Queues a turn behind the pending ones without sending a [`TurnEvent`].
+    pub fn push(&mut self, turn: PuzzleTurn) {
+        self.pending.push_back(turn);
+    }
+
+    /// Finishes the active turn and every turn queued before the queue runs empty without
+    /// animating them, which includes turns requested during the same frame.
+    pub fn skip_animations(&mut self) {
+        self.skipping = true;
+    }
+
+    /// Forgets all turns, e.g. because the pieces they would turn got despawned.
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.active = None;
+        self.skipping = false;
+    }
+}
+
+#[derive(Debug)]
+struct ActiveTurn {
+    turn: PuzzleTurn,
+    pivot: Entity,
+    pieces: Vec<(usize, Entity)>,
+    axis: Vec3,
+    /// Point the axis goes through.
+    center: Vec3,
+    angle: f32,
+    duration: f32,
+    elapsed: f32,
+}
+
+fn queue_turns(mut turn_events: EventReader<TurnEvent>, mut turn_queue: ResMut<TurnQueue>) {
+    turn_queue
+        .pending
+        .extend(turn_events.iter().map(|event| event.0.clone()));
+}
+
+fn animate_turns(
+    mut commands: Commands,
+    mut turn_queue: ResMut<TurnQueue>,
+    puzzle: Option<ResMut<CurrentPuzzle>>,
+    piece_entities: Res<PieceEntities>,
+    spawned_puzzle: Res<SpawnedPuzzle>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut solved_events: EventWriter<SolvedEvent>,
+    turn_settings: Res<TurnSettings>,
+    time: Res<Time>,
+    view_query: Query<Entity, With<View>>,
+    mut transform_query: Query<&mut Transform>,
+) {
+    let Some(mut puzzle) = puzzle else {
+        turn_queue.clear();
+        return;
+    };
+
+    let view_entity = view_query.single();
+
+    // chunks touched by the turns of this frame, which only get created again once, so that
+    // skipping many turns doesn't rebuild them for every single one
+    let mut dirty_chunks = Vec::new();
+
+    loop {
+        if turn_queue.active.is_none() {
+            let Some(turn) = turn_queue.pending.pop_front() else {
+                turn_queue.skipping = false;
+                break;
+            };
+
+            let was_solved = puzzle.0.is_solved();
+
+            // the turning layers get a mesh of their own, showing them before the turn
+            let layers_mesh = match puzzle.0.chunks() {
+                Some(chunks) => {
+                    dirty_chunks.extend(chunks.chunks_in_layers(turn.axis, turn.layers.clone()));
+
+                    (!turn_queue.skipping)
+                        .then(|| chunks.create_layers_mesh(turn.axis, turn.layers.clone()))
+                }
+                None => None,
+            };
+
+            let active_turn = start_turn(
+                &mut commands,
+                puzzle.0.as_mut(),
+                &turn,
+                &turn_settings,
+                view_entity,
+                &piece_entities,
+            );
+
+            if let (Some(mesh), Some(material)) = (layers_mesh, &spawned_puzzle.material) {
+                let mesh = meshes.add(mesh);
+
+                commands.entity(active_turn.pivot).with_children(|builder| {
+                    builder.spawn(PbrBundle {
+                        mesh,
+                        material: material.clone(),
+                        ..default()
+                    });
+                });
+            }
+
+            turn_queue.active = Some(active_turn);
+
+            if !was_solved && puzzle.0.is_solved() {
+                solved_events.send(SolvedEvent);
+            }
+        }
+
+        let skipping = turn_queue.skipping;
+        let active_turn = turn_queue.active.as_mut().unwrap();
+
+        active_turn.elapsed += time.delta_seconds();
+        let progress = if skipping {
+            1.0
+        } else {
+            (active_turn.elapsed / active_turn.duration).min(1.0)
+        };
+
+        if let Ok(mut pivot_transform) = transform_query.get_mut(active_turn.pivot) {
+            let rotation =
+                Quat::from_axis_angle(active_turn.axis, active_turn.angle * ease_in_out(progress));
+
+            // rotates around the center instead of the origin of the view
+            pivot_transform.rotation = rotation;
+            pivot_transform.translation = active_turn.center - rotation * active_turn.center;
+        }
+
+        if progress < 1.0 {
+            break;
+        }
+
+        let active_turn = turn_queue.active.take().unwrap();
+        let entities = active_turn
+            .pieces
+            .iter()
+            .map(|(_, entity)| *entity)
+            .collect::<Vec<_>>();
+
+        for (piece, entity) in active_turn.pieces {
+            if let Ok(mut transform) = transform_query.get_mut(entity) {
+                *transform = puzzle.0.get_piece_transform(piece);
+            }
+        }
+
+        if let Some(chunks) = puzzle.0.chunks() {
+            let turn = active_turn.turn;
+            dirty_chunks.extend(chunks.chunks_in_layers(turn.axis, turn.layers));
+        }
+
+        commands.entity(view_entity).push_children(&entities);
+        commands.entity(active_turn.pivot).despawn_recursive();
+
+        // otherwise the next turn starts in the next frame
+        if !skipping {
+            break;
+        }
+    }
+
+    let Some(chunks) = puzzle.0.chunks() else {
+        return;
+    };
+
+    if dirty_chunks.is_empty() {
+        return;
+    }
+
+    dirty_chunks.sort_unstable();
+    dirty_chunks.dedup();
+
+    // the layers which are still turning stay left out, as their own mesh shows them
+    let turning = turn_queue
+        .active
+        .as_ref()
+        .map(|active_turn| (active_turn.turn.axis, active_turn.turn.layers.clone()));
+
+    let chunk_meshes = chunks.create_chunk_meshes(&dirty_chunks, turning);
+
+    for (chunk, mesh) in dirty_chunks.into_iter().zip(chunk_meshes) {
+        let handle = spawned_puzzle.chunks.get(chunk);
+
+        if let Some(chunk_mesh) = handle.and_then(|handle| meshes.get_mut(handle)) {
+            *chunk_mesh = mesh;
+        }
+    }
+}
+
+/// Applies the turn to the logical state and groups the affected pieces under a pivot,
+/// whose rotation gets animated until the pieces are baked back into the view.
+fn start_turn(
+    commands: &mut Commands,
+    puzzle: &mut dyn PuzzleRender,
+    turn: &PuzzleTurn,
+    turn_settings: &TurnSettings,
+    view_entity: Entity,
+    piece_entities: &PieceEntities,
+) -> ActiveTurn {
+    let affected = puzzle.pieces_in_layers(turn.axis, turn.layers.clone());
+    puzzle.turn(turn);
+
+    let pieces = affected
+        .into_iter()
+        .filter_map(|piece| piece_entities.0.get(piece).map(|entity| (piece, *entity)))
+        .collect::<Vec<_>>();
+
+    let mut pivot = None;
+
+    commands.entity(view_entity).with_children(|builder| {
+        pivot = Some(builder.spawn(SpatialBundle::VISIBLE_IDENTITY).id());
+    });
+
+    let pivot = pivot.unwrap();
+
+    commands
+        .entity(pivot)
+        .push_children(&pieces.iter().map(|(_, entity)| *entity).collect::<Vec<_>>());
+
+    // the shortest way around, e.g. three quarter turns are one quarter turn back
+    let steps_per_revolution = puzzle.steps_per_revolution();
+    let steps = match turn.amount.rem_euclid(steps_per_revolution) {
+        steps if steps > steps_per_revolution / 2 => steps - steps_per_revolution,
+        steps => steps,
+    };
+
+    // positive steps are clockwise, which is a negative rotation around the axis
+    let angle = -(steps as f32) * puzzle.turn_angle();
+    let duration = turn_settings.duration * (1.0 + (steps.abs() - 1).max(0) as f32 / 2.0);
+
+    ActiveTurn {
+        turn: turn.clone(),
+        pivot,
+        pieces,
+        axis: puzzle.axes()[turn.axis],
+        center: puzzle.pivot(),
+        angle,
+        duration,
+        elapsed: 0.0,
+    }
+}
+
+fn ease_in_out(t: f32) -> f32 {
+    if t < 0.5 {
+        4.0 * t * t * t
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+    }
+}
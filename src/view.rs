@@ -1,23 +1,36 @@
 use bevy::input::mouse::MouseMotion;
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 pub struct ViewPlugin;
 
 impl Plugin for ViewPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(ViewRotation::default())
+            .init_resource::<ViewLock>()
             .add_startup_system(setup)
-            .add_system(change_view);
+            .add_system(change_view.label("change_view"));
     }
 }
 
 #[derive(Component)]
 pub struct View;
 
+/// Keeps dragging from rotating the view, e.g. while the drag turns a layer instead.
 #[derive(Debug, Default, Resource)]
-struct ViewRotation {
-    pitch: f32,
-    yaw: f32,
+pub struct ViewLock(pub bool);
+
+/// How far the view is rotated by dragging, in radians.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Resource, Serialize, Deserialize)]
+pub struct ViewRotation {
+    pub pitch: f32,
+    pub yaw: f32,
+}
+
+impl ViewRotation {
+    pub fn to_quat(self) -> Quat {
+        Quat::from_euler(EulerRot::XYZ, self.pitch, self.yaw, 0.0)
+    }
 }
 
 fn setup(mut commands: Commands) {
@@ -26,6 +39,7 @@ fn setup(mut commands: Commands) {
 
 fn change_view(
     mut view_rotation: ResMut<ViewRotation>,
+    view_lock: Res<ViewLock>,
     mouse_button_input: Res<Input<MouseButton>>,
     mut mouse_motion_events: EventReader<MouseMotion>,
     touches: Res<Touches>,
@@ -45,6 +59,10 @@ fn change_view(
         return;
     }
 
+    if view_lock.0 {
+        return;
+    }
+
     let sensitivity = 0.25;
     let delta_time = time.delta_seconds();
 
@@ -60,5 +78,5 @@ fn change_view(
     }
 
     let mut view = query.single_mut();
-    view.rotation = Quat::from_euler(EulerRot::XYZ, view_rotation.pitch, view_rotation.yaw, 0.0);
+    view.rotation = view_rotation.to_quat();
 }